@@ -0,0 +1,21 @@
+use std::env;
+use std::path::PathBuf;
+
+use cargo_emit::rerun_if_changed;
+
+/// Generates the C header of the library in OUT_DIR, which checks that cbindgen can generate it.
+///
+/// The checked-in copy in the include directory of the crate is generated by `cargo xtask header`.
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("set by cargo"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("set by cargo"));
+
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).expect("cbindgen.toml is valid"))
+        .generate()
+        .expect("Unable to generate the C header")
+        .write_to_file(out_dir.join("parse_checker.h"));
+
+    rerun_if_changed!("src/lib.rs", "cbindgen.toml");
+}
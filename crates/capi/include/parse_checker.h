@@ -0,0 +1,114 @@
+#ifndef PARSE_CHECKER_H
+#define PARSE_CHECKER_H
+
+/* This file is generated by `cargo xtask header`, do not edit it. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ The input is an mCRL2 specification.
+ */
+#define PARSE_CHECKER_KIND_MCRL2 0
+
+/*
+ The input is a modal formula.
+ */
+#define PARSE_CHECKER_KIND_MCF 1
+
+/*
+ The input is a data expression.
+ */
+#define PARSE_CHECKER_KIND_DATA_EXPRESSION 2
+
+/*
+ Both releases produce the same AST.
+ */
+#define PARSE_CHECKER_IDENTICAL 0
+
+/*
+ The releases produce different ASTs.
+ */
+#define PARSE_CHECKER_DIFFERENCE 1
+
+/*
+ One of the releases cannot parse the input.
+ */
+#define PARSE_CHECKER_PARSE_ERROR 3
+
+/*
+ The input could not be checked, for example because the mcrl2-2024 helper cannot be found, the arguments are invalid
+ or the checker panicked.
+ */
+#define PARSE_CHECKER_INTERNAL_ERROR 4
+
+/*
+ The result of comparing an input, where every string is either NULL or a NUL-terminated UTF-8 string.
+ */
+typedef struct ParseCheckerResult {
+  /*
+   One of the PARSE_CHECKER_IDENTICAL, PARSE_CHECKER_DIFFERENCE, PARSE_CHECKER_PARSE_ERROR and PARSE_CHECKER_INTERNAL_ERROR codes.
+   */
+  int32_t status;
+  /*
+   The AST printed by the 202407.1 release, or NULL when it cannot parse the input.
+   */
+  char *previous_ast;
+  /*
+   The AST printed by the 202507.0 release, or NULL when it cannot parse the input.
+   */
+  char *current_ast;
+  /*
+   The error of the 202407.1 release, or NULL when it can parse the input.
+   */
+  char *previous_error;
+  /*
+   The error of the 202507.0 release, or NULL when it can parse the input.
+   */
+  char *current_error;
+} ParseCheckerResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Compares how both releases parse the NUL-terminated UTF-8 input of the given kind.
+
+ Differences that are suppressed by a `% parse-checker:` comment count as identical.
+ The result is never NULL and must be released with parse_checker_free.
+
+ # Safety
+
+ The input must be NULL or point to a NUL-terminated string.
+ */
+struct ParseCheckerResult *parse_checker_compare(const char *input, uint32_t kind);
+
+/*
+ Releases a result of parse_checker_compare, where NULL is ignored.
+
+ # Safety
+
+ The result must be NULL or returned by parse_checker_compare, and it must not be used afterwards.
+ */
+void parse_checker_free(struct ParseCheckerResult *result);
+
+/*
+ Uses the mcrl2-2024 helper at the given NUL-terminated path, which is otherwise searched for in PATH.
+
+ Returns zero on success, and PARSE_CHECKER_INTERNAL_ERROR when the path is NULL or not valid UTF-8, or when the
+ checker panicked.
+
+ # Safety
+
+ The path must be NULL or point to a NUL-terminated string.
+ */
+int32_t parse_checker_set_helper_path(const char *path);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PARSE_CHECKER_H */
@@ -0,0 +1,234 @@
+//! A C API for comparing how the 202407.1 and 202507.0 releases of mCRL2 parse an input.
+//!
+//! The header `include/parse_checker.h` is generated by `cargo xtask header`. An
+//! input is compared with [parse_checker_compare], which returns a result that
+//! must be released with [parse_checker_free], for example:
+//!
+//! ```c
+//! ParseCheckerResult *result = parse_checker_compare("[true*]<true>true", PARSE_CHECKER_KIND_MCF);
+//! if (result->status == PARSE_CHECKER_DIFFERENCE) {
+//!     printf("%s\n%s\n", result->previous_ast, result->current_ast);
+//! }
+//! parse_checker_free(result);
+//! ```
+//!
+//! All functions can be called from multiple threads, but the inputs are parsed one at a time.
+
+use std::error::Error;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::panic;
+use std::path::PathBuf;
+use std::ptr;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use parse_checker_core::Checker;
+use parse_checker_core::InputKind;
+use parse_checker_core::Outcome;
+
+/// The input is an mCRL2 specification.
+pub const PARSE_CHECKER_KIND_MCRL2: u32 = 0;
+
+/// The input is a modal formula.
+pub const PARSE_CHECKER_KIND_MCF: u32 = 1;
+
+/// The input is a data expression.
+pub const PARSE_CHECKER_KIND_DATA_EXPRESSION: u32 = 2;
+
+/// Both releases produce the same AST.
+pub const PARSE_CHECKER_IDENTICAL: i32 = 0;
+
+/// The releases produce different ASTs.
+pub const PARSE_CHECKER_DIFFERENCE: i32 = 1;
+
+/// One of the releases cannot parse the input.
+pub const PARSE_CHECKER_PARSE_ERROR: i32 = 3;
+
+/// The input could not be checked, for example because the mcrl2-2024 helper cannot be found, the arguments are invalid
+/// or the checker panicked.
+pub const PARSE_CHECKER_INTERNAL_ERROR: i32 = 4;
+
+/// The checker that is used by all functions, which is replaced by [parse_checker_set_helper_path].
+static CHECKER: LazyLock<Mutex<Checker>> = LazyLock::new(|| Mutex::new(Checker::with_persistent_helper(None)));
+
+/// Returns the checker, which is still used after a panic while it was locked.
+fn checker() -> MutexGuard<'static, Checker> {
+    CHECKER.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The result of comparing an input, where every string is either NULL or a NUL-terminated UTF-8 string.
+#[repr(C)]
+pub struct ParseCheckerResult {
+    /// One of the PARSE_CHECKER_IDENTICAL, PARSE_CHECKER_DIFFERENCE, PARSE_CHECKER_PARSE_ERROR and PARSE_CHECKER_INTERNAL_ERROR codes.
+    pub status: i32,
+
+    /// The AST printed by the 202407.1 release, or NULL when it cannot parse the input.
+    pub previous_ast: *mut c_char,
+
+    /// The AST printed by the 202507.0 release, or NULL when it cannot parse the input.
+    pub current_ast: *mut c_char,
+
+    /// The error of the 202407.1 release, or NULL when it can parse the input.
+    pub previous_error: *mut c_char,
+
+    /// The error of the 202507.0 release, or NULL when it can parse the input.
+    pub current_error: *mut c_char,
+}
+
+/// Compares how both releases parse the NUL-terminated UTF-8 input of the given kind.
+///
+/// Differences that are suppressed by a `% parse-checker:` comment count as identical.
+/// The result is never NULL and must be released with parse_checker_free.
+///
+/// # Safety
+///
+/// The input must be NULL or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parse_checker_compare(input: *const c_char, kind: u32) -> *mut ParseCheckerResult {
+    // Panics must not unwind into the caller, so they are reported as internal errors.
+    let result = panic::catch_unwind(|| {
+        // SAFETY: The caller guarantees that a non-NULL input is a NUL-terminated string.
+        unsafe { compare(input, kind) }
+    })
+    .unwrap_or_else(|_| Err("The checker panicked".into()));
+
+    let result = match result {
+        Ok(result) => result,
+        Err(error) => ParseCheckerResult {
+            status: PARSE_CHECKER_INTERNAL_ERROR,
+            previous_ast: ptr::null_mut(),
+            current_ast: ptr::null_mut(),
+            previous_error: into_c_string(error.to_string()),
+            current_error: into_c_string(error.to_string()),
+        },
+    };
+
+    Box::into_raw(Box::new(result))
+}
+
+/// Compares the input with both releases, and fails when the arguments are invalid.
+///
+/// # Safety
+///
+/// See [parse_checker_compare].
+unsafe fn compare(input: *const c_char, kind: u32) -> Result<ParseCheckerResult, Box<dyn Error>> {
+    if input.is_null() {
+        return Err("The input is NULL".into());
+    }
+
+    // SAFETY: The input is not NULL, so it is a NUL-terminated string.
+    let input = unsafe { CStr::from_ptr(input) }.to_str()?;
+    let kind = match kind {
+        PARSE_CHECKER_KIND_MCRL2 => InputKind::Mcrl2,
+        PARSE_CHECKER_KIND_MCF => InputKind::Mcf,
+        PARSE_CHECKER_KIND_DATA_EXPRESSION => InputKind::DataExpression,
+        _ => return Err(format!("Unknown input kind {}", kind).into()),
+    };
+
+    let outcome = checker().check(input, kind);
+    let (previous_ast, previous_error) = split(outcome.previous);
+    let (current_ast, current_error) = split(outcome.current);
+
+    Ok(ParseCheckerResult {
+        status: match outcome.outcome {
+            Outcome::Identical => PARSE_CHECKER_IDENTICAL,
+            Outcome::Difference => PARSE_CHECKER_DIFFERENCE,
+            Outcome::ParseError => PARSE_CHECKER_PARSE_ERROR,
+            Outcome::InternalError => PARSE_CHECKER_INTERNAL_ERROR,
+        },
+        previous_ast,
+        current_ast,
+        previous_error,
+        current_error,
+    })
+}
+
+/// Returns the AST and the error of a release as C strings, where the missing one is NULL.
+fn split(result: Result<String, Box<dyn Error>>) -> (*mut c_char, *mut c_char) {
+    match result {
+        Ok(ast) => (into_c_string(ast), ptr::null_mut()),
+        Err(error) => (ptr::null_mut(), into_c_string(error.to_string())),
+    }
+}
+
+/// Returns the text as a C string that is owned by the caller, where NUL characters are removed.
+fn into_c_string(text: String) -> *mut c_char {
+    CString::new(text.replace('\0', ""))
+        .expect("NUL characters are removed")
+        .into_raw()
+}
+
+/// Releases a result of parse_checker_compare, where NULL is ignored.
+///
+/// # Safety
+///
+/// The result must be NULL or returned by parse_checker_compare, and it must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parse_checker_free(result: *mut ParseCheckerResult) {
+    if result.is_null() {
+        return;
+    }
+
+    // SAFETY: The result and its strings are allocated by parse_checker_compare, and are not used afterwards.
+    unsafe {
+        let result = Box::from_raw(result);
+        for text in [result.previous_ast, result.current_ast, result.previous_error, result.current_error] {
+            if !text.is_null() {
+                drop(CString::from_raw(text));
+            }
+        }
+    }
+}
+
+/// Uses the mcrl2-2024 helper at the given NUL-terminated path, which is otherwise searched for in PATH.
+///
+/// Returns zero on success, and PARSE_CHECKER_INTERNAL_ERROR when the path is NULL or not valid UTF-8, or when the
+/// checker panicked.
+///
+/// # Safety
+///
+/// The path must be NULL or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn parse_checker_set_helper_path(path: *const c_char) -> i32 {
+    if path.is_null() {
+        return PARSE_CHECKER_INTERNAL_ERROR;
+    }
+
+    // Panics must not unwind into the caller, so they are reported as internal errors.
+    panic::catch_unwind(|| {
+        // SAFETY: The path is not NULL, so it is a NUL-terminated string.
+        match unsafe { CStr::from_ptr(path) }.to_str() {
+            Ok(path) => {
+                *checker() = Checker::with_persistent_helper(Some(PathBuf::from(path)));
+                0
+            }
+            Err(_) => PARSE_CHECKER_INTERNAL_ERROR,
+        }
+    })
+    .unwrap_or(PARSE_CHECKER_INTERNAL_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_arguments() {
+        // SAFETY: The results are returned by parse_checker_compare and are freed once.
+        unsafe {
+            let result = parse_checker_compare(ptr::null(), PARSE_CHECKER_KIND_MCF);
+            assert_eq!((*result).status, PARSE_CHECKER_INTERNAL_ERROR);
+            assert!((*result).previous_ast.is_null());
+            assert_eq!(CStr::from_ptr((*result).current_error).to_str(), Ok("The input is NULL"));
+            parse_checker_free(result);
+
+            let result = parse_checker_compare(c"true".as_ptr(), 42);
+            assert_eq!((*result).status, PARSE_CHECKER_INTERNAL_ERROR);
+            parse_checker_free(result);
+        }
+    }
+}
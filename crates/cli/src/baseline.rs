@@ -0,0 +1,194 @@
+//! A baseline of reviewed parsing differences that are accepted in later runs.
+//!
+//! Every accepted difference is stored as the path of the file, relative to the
+//! directory of the baseline, and a fingerprint of the ASTs of both releases.
+//! A difference that is changed, for example by editing the corresponding
+//! formula, therefore results in a new fingerprint and is reported again.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Difference;
+use crate::report::FileReport;
+
+/// A single accepted difference.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BaselineEntry {
+    /// The path of the file, relative to the directory of the baseline and with forward slashes.
+    pub path: String,
+
+    /// The fingerprint of the difference, see [fingerprint].
+    pub fingerprint: String,
+}
+
+/// The contents of a baseline file.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BaselineFile {
+    accepted: Vec<BaselineEntry>,
+}
+
+/// The accepted differences.
+pub struct Baseline {
+    /// The directory that contains the baseline file.
+    root: PathBuf,
+
+    /// The accepted differences.
+    entries: BTreeSet<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Reads the baseline from the given file.
+    pub fn read(path: &Path) -> Result<Baseline, Box<dyn Error>> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("Cannot read baseline {}: {}", path.display(), e))?;
+        let file: BaselineFile =
+            toml::from_str(&contents).map_err(|e| format!("Cannot parse baseline {}: {}", path.display(), e))?;
+
+        Ok(Baseline {
+            root: root_of(path)?,
+            entries: file.accepted.into_iter().collect(),
+        })
+    }
+
+    /// Creates a baseline for the given file that accepts all differences in the reports.
+    pub fn from_reports(path: &Path, reports: &[FileReport]) -> Result<Baseline, Box<dyn Error>> {
+        let root = root_of(path)?;
+
+        let mut entries = BTreeSet::new();
+        for report in reports {
+            for difference in report.differences() {
+                entries.insert(BaselineEntry {
+                    path: relative_path(&root, &report.path),
+                    fingerprint: fingerprint(&difference),
+                });
+            }
+        }
+
+        Ok(Baseline { root, entries })
+    }
+
+    /// Writes the baseline to the given file.
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let file = BaselineFile {
+            accepted: self.entries.iter().cloned().collect(),
+        };
+
+        let contents = format!(
+            "# The parsing differences that have been reviewed and accepted, written by parse-checker.\n\n{}",
+            toml::to_string(&file)?
+        );
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Marks the differences in the reports that are accepted by the baseline.
+    ///
+    /// Returns the entries for the checked files that no longer match any difference.
+    pub fn apply(&self, reports: &mut [FileReport]) -> Vec<BaselineEntry> {
+        let mut used = BTreeSet::new();
+        let mut checked = BTreeSet::new();
+
+        for report in reports.iter_mut() {
+            let path = relative_path(&self.root, &report.path);
+
+            let accepted: Vec<String> = report
+                .differences()
+                .iter()
+                .map(fingerprint)
+                .filter(|fingerprint| {
+                    self.entries.contains(&BaselineEntry {
+                        path: path.clone(),
+                        fingerprint: fingerprint.clone(),
+                    })
+                })
+                .collect();
+
+            for fingerprint in &accepted {
+                used.insert(BaselineEntry {
+                    path: path.clone(),
+                    fingerprint: fingerprint.clone(),
+                });
+            }
+
+            report.accepted.extend(accepted);
+            checked.insert(path);
+        }
+
+        self.entries
+            .iter()
+            .filter(|entry| checked.contains(&entry.path) && !used.contains(*entry))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Returns the fingerprint of a difference, which is a hash of the whitespace normalized ASTs of both releases.
+pub fn fingerprint(difference: &Difference) -> String {
+    // The 64-bit FNV-1a hash, which unlike the standard library hasher is stable between releases.
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    let previous = difference.previous.split_whitespace();
+    let current = difference.current.split_whitespace();
+    for word in previous.chain(["\0"]).chain(current) {
+        for byte in word.bytes().chain([b' ']) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    format!("{hash:016x}")
+}
+
+/// Returns the directory containing the given file.
+fn root_of(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+    Ok(fs::canonicalize(parent.unwrap_or(Path::new(".")))?)
+}
+
+/// Returns the path of the file relative to the given root, using forward slashes on every platform.
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = fs::canonicalize(path)
+        .ok()
+        .and_then(|path| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf());
+
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_whitespace() {
+        let difference = Difference {
+            original: None,
+            current: "form ((a) && (b)) => (c)",
+            previous: "form (a) && ((b) => (c))",
+        };
+        let reformatted = Difference {
+            original: None,
+            current: "form ((a) && (b))\n  => (c)",
+            previous: "form (a) &&\n  ((b) => (c))",
+        };
+        let swapped = Difference {
+            original: None,
+            current: difference.previous,
+            previous: difference.current,
+        };
+
+        assert_eq!(fingerprint(&difference), fingerprint(&reformatted));
+        assert_ne!(fingerprint(&difference), fingerprint(&swapped));
+    }
+}
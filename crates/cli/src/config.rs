@@ -0,0 +1,354 @@
+//! The project configuration that is read from a `parse-checker.toml` file.
+//!
+//! The configuration file is found by walking up from the current directory,
+//! and all paths and glob patterns in it are relative to the directory that
+//! contains the file. For example:
+//!
+//! ```toml
+//! include = ["**/*.mcf", "**/*.mcrl2", "properties/**/*.txt"]
+//! exclude = ["build/**"]
+//! format = "markdown"
+//! diff = "unified"
+//! fail-on = ["difference", "parse-error"]
+//! helper = "tools/mcrl2-2024"
+//!
+//! [[overrides]]
+//! paths = ["properties/**/*.txt"]
+//! kind = "mcf"
+//!
+//! [[backends]]
+//! name = "fork"
+//! version = "202507.0-fork"
+//! path = "tools/mcrl2-fork"
+//! ```
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use globset::GlobBuilder;
+use globset::GlobSet;
+use globset::GlobSetBuilder;
+use serde::Deserialize;
+
+use crate::Checker;
+use crate::Registry;
+use crate::DiffLayout;
+use crate::FailOn;
+use crate::ReportFormat;
+use crate::SubprocessBackend;
+use crate::is_document;
+use crate::kind_directive;
+
+/// The name of the configuration file.
+pub const CONFIG_FILE_NAME: &str = "parse-checker.toml";
+
+/// The contents of a configuration file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    overrides: Vec<Override>,
+    format: Option<ReportFormat>,
+    diff: Option<DiffLayout>,
+    fail_on: Option<Vec<FailOn>>,
+    helper: Option<PathBuf>,
+    backends: Vec<ExternalBackend>,
+}
+
+/// Overrides the input kind for the files matching the given glob patterns.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Override {
+    paths: Vec<String>,
+    kind: OverrideKind,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OverrideKind {
+    Mcf,
+    Mcrl2,
+}
+
+/// A backend executable with the command line interface of the mcrl2-2024 helper.
+///
+/// It reads the input from stdin, parses a modal formula when `--mcf` is given and
+/// prints the AST, where a parse error results in exit code 1 and the error on stderr.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalBackend {
+    /// The name that selects the backend, for example with `--from`.
+    pub name: String,
+
+    /// The version label of the release, which is shown in the headers of differences.
+    pub version: Option<String>,
+
+    /// The location of the executable, which is searched for in PATH when it is only a file name.
+    pub path: PathBuf,
+}
+
+impl FromStr for ExternalBackend {
+    type Err = String;
+
+    /// Parses `NAME=PATH` or `NAME@VERSION=PATH`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (label, path) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected NAME=PATH or NAME@VERSION=PATH, but got {}", s))?;
+        let (name, version) = match label.split_once('@') {
+            Some((name, version)) => (name, Some(version.to_string())),
+            None => (label, None),
+        };
+
+        if name.is_empty() || path.is_empty() {
+            return Err(format!("Expected NAME=PATH or NAME@VERSION=PATH, but got {}", s));
+        }
+
+        Ok(ExternalBackend {
+            name: name.to_string(),
+            version,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+/// The project configuration, where an empty configuration is used when no configuration file is found.
+#[derive(Default)]
+pub struct Config {
+    /// The directory that contains the configuration file.
+    root: PathBuf,
+
+    /// The files that are checked when searching directories, or all .mcf, .mcrl2 and document files when not set.
+    include: Option<GlobSet>,
+
+    /// The files that are skipped when searching directories.
+    exclude: GlobSet,
+
+    /// The files for which the input kind is overridden, where the first matching override is used.
+    overrides: Vec<(GlobSet, bool)>,
+
+    /// The default format of reports.
+    pub format: Option<ReportFormat>,
+
+    /// The default layout of printed differences.
+    pub diff: Option<DiffLayout>,
+
+    /// The default outcomes that result in a non-zero exit code.
+    pub fail_on: Option<Vec<FailOn>>,
+
+    /// The location of the mcrl2-2024 helper executable.
+    pub helper: Option<PathBuf>,
+
+    /// The additional backend executables, in the order of the configuration file.
+    pub backends: Vec<ExternalBackend>,
+}
+
+impl Config {
+    /// Reads the configuration file that is found by walking up from the current directory.
+    pub fn find() -> Result<Config, Box<dyn Error>> {
+        let current_dir = env::current_dir()?;
+
+        for directory in current_dir.ancestors() {
+            let path = directory.join(CONFIG_FILE_NAME);
+            if path.is_file() {
+                return Config::read(&path);
+            }
+        }
+
+        Ok(Config::default())
+    }
+
+    /// Reads the given configuration file.
+    pub fn read(path: &Path) -> Result<Config, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)?;
+        let file: ConfigFile =
+            toml::from_str(&contents).map_err(|e| format!("Cannot parse {}: {}", path.display(), e))?;
+
+        let root = fs::canonicalize(path)?
+            .parent()
+            .expect("a file always has a parent directory")
+            .to_path_buf();
+
+        let include = if file.include.is_empty() {
+            None
+        } else {
+            Some(build_globs(&file.include)?)
+        };
+
+        let overrides = file
+            .overrides
+            .iter()
+            .map(|o| Ok((build_globs(&o.paths)?, matches!(o.kind, OverrideKind::Mcf))))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(Config {
+            include,
+            exclude: build_globs(&file.exclude)?,
+            overrides,
+            format: file.format,
+            diff: file.diff,
+            fail_on: file.fail_on,
+            helper: file.helper.map(|helper| root.join(helper)),
+            backends: file
+                .backends
+                .into_iter()
+                .map(|backend| ExternalBackend {
+                    // Paths with a directory are relative to the configuration file, others are searched for in PATH.
+                    path: if backend.path.components().count() > 1 {
+                        root.join(&backend.path)
+                    } else {
+                        backend.path
+                    },
+                    ..backend
+                })
+                .collect(),
+            root,
+        })
+    }
+
+    /// Returns true iff the file should be checked when it is found while searching a directory.
+    pub fn is_included(&self, path: &Path) -> bool {
+        let relative = self.relative(path);
+        if self.exclude.is_match(&relative) {
+            return false;
+        }
+
+        match &self.include {
+            Some(include) => include.is_match(&relative),
+            None => path.extension().is_some_and(|ext| ext == "mcf" || ext == "mcrl2") || is_document(path),
+        }
+    }
+
+    /// Returns true iff the file with the given contents should be checked as a modal formula.
+    ///
+    /// A `% parse-checker: kind=...` directive on the first line takes precedence over an
+    /// explicit `--mcf`, which applies to files without a .mcrl2 extension, and then over the
+    /// overrides in the configuration, which in turn take precedence over [crate::is_mcf].
+    pub fn is_mcf(&self, path: &Path, input: &str, mcf: bool) -> bool {
+        if let Some(mcf) = kind_directive(input) {
+            return mcf;
+        }
+
+        if mcf && crate::is_mcf(path, input, mcf) {
+            return true;
+        }
+
+        let relative = self.relative(path);
+
+        self.overrides
+            .iter()
+            .find(|(globs, _)| globs.is_match(&relative))
+            .map_or_else(|| crate::is_mcf(path, input, mcf), |(_, mcf)| *mcf)
+    }
+
+    /// Returns a checker that uses the configured helper, and reuses one helper process for all inputs.
+    pub fn checker(&self) -> Checker {
+        Checker::with_persistent_helper(self.helper.clone())
+    }
+
+    /// Returns the backends that can be compared, which are the configured backends followed by the given ones.
+    ///
+    /// The 202407.1 release uses the configured helper, and the names of all backends must differ.
+    pub fn registry(&self, backends: &[ExternalBackend]) -> Result<Registry, Box<dyn Error>> {
+        let mut registry = Registry::from_checker(&self.checker());
+
+        for backend in self.backends.iter().chain(backends) {
+            let mut subprocess = SubprocessBackend::new(&backend.name, Some(backend.path.clone()));
+            if let Some(version) = &backend.version {
+                subprocess = subprocess.with_version(version);
+            }
+
+            registry.register(Arc::new(subprocess))?;
+        }
+
+        Ok(registry)
+    }
+
+    /// Returns the path relative to the directory of the configuration file, which is used to match the glob patterns.
+    fn relative(&self, path: &Path) -> PathBuf {
+        fs::canonicalize(path)
+            .ok()
+            .and_then(|path| path.strip_prefix(&self.root).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+/// Builds a set of glob patterns, where `*` does not match the path separator but `**` does.
+fn build_globs(patterns: &[String]) -> Result<GlobSet, Box<dyn Error>> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+    }
+
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_globs() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path();
+        fs::create_dir_all(directory.join("properties")).unwrap();
+        fs::create_dir_all(directory.join("specs")).unwrap();
+        fs::write(
+            directory.join(CONFIG_FILE_NAME),
+            r#"include = ["**/*.mcf", "properties/*.txt"]
+exclude = ["old/**"]
+
+[[overrides]]
+paths = ["properties/*.txt"]
+kind = "mcf"
+
+[[overrides]]
+paths = ["specs/*.txt"]
+kind = "mcrl2"
+"#,
+        )
+        .unwrap();
+        fs::write(directory.join("properties/deadlock.txt"), "[true*]<true>true").unwrap();
+        fs::write(directory.join("notes.txt"), "").unwrap();
+        fs::write(directory.join("specs/deadlock.txt"), "<true>true").unwrap();
+
+        let config = Config::read(&directory.join(CONFIG_FILE_NAME)).unwrap();
+
+        assert!(config.is_included(&directory.join("properties/deadlock.txt")));
+        assert!(!config.is_included(&directory.join("notes.txt")));
+        assert!(config.is_mcf(&directory.join("properties/deadlock.txt"), "init delta;", false));
+        assert!(!config.is_mcf(&directory.join("notes.txt"), "init delta;", false));
+        assert!(config.is_mcf(
+            &directory.join("notes.txt"),
+            "% parse-checker: kind=mcf\ninit delta;",
+            false
+        ));
+
+        // An explicit --mcf takes precedence over the overrides.
+        assert!(!config.is_mcf(&directory.join("specs/deadlock.txt"), "<true>true", false));
+        assert!(config.is_mcf(&directory.join("specs/deadlock.txt"), "<true>true", true));
+        assert!(config.helper.is_none());
+    }
+
+    #[test]
+    fn test_external_backend() {
+        assert_eq!(
+            "fork@202507.0-fork=tools/mcrl2-fork".parse(),
+            Ok(ExternalBackend {
+                name: "fork".to_string(),
+                version: Some("202507.0-fork".to_string()),
+                path: PathBuf::from("tools/mcrl2-fork"),
+            })
+        );
+        assert_eq!("next=mcrl2-next".parse::<ExternalBackend>().unwrap().version, None);
+        assert!("mcrl2-next".parse::<ExternalBackend>().is_err());
+        assert!("=mcrl2-next".parse::<ExternalBackend>().is_err());
+    }
+}
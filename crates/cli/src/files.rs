@@ -0,0 +1,169 @@
+//! Collects the input files that must be checked.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::Config;
+use crate::statements::directive;
+
+/// The keywords that start a section of an mCRL2 specification.
+const SPECIFICATION_KEYWORDS: [&str; 9] = ["sort", "cons", "map", "var", "eqn", "act", "proc", "init", "glob"];
+
+/// The tokens that can start a modal formula.
+const FORMULA_KEYWORDS: [&str; 15] = [
+    "form", "nu", "mu", "forall", "exists", "true", "false", "val", "delay", "yaled", "[", "<", "!", "(", "-",
+];
+
+/// Returns true iff the file at the given path should be checked as a modal formula.
+///
+/// Files with a .mcf extension are modal formulas and files with a .mcrl2
+/// extension are specifications, which is also what the toolset does. Any
+/// other file is a modal formula if `mcf` is set, and otherwise its kind is
+/// determined by its contents, see [sniff_mcf].
+pub fn is_mcf(path: &Path, input: &str, mcf: bool) -> bool {
+    match path.extension() {
+        Some(ext) if ext == "mcf" => true,
+        Some(ext) if ext == "mcrl2" => false,
+        _ => mcf || sniff_mcf(input),
+    }
+}
+
+/// Returns the input kind given by a `% parse-checker: kind=mcf` or `% parse-checker: kind=mcrl2` directive on the first line.
+pub fn kind_directive(input: &str) -> Option<bool> {
+    let first_line = input.trim_start_matches('\u{feff}').lines().next()?.trim();
+    let comment = first_line.strip_prefix('%')?;
+
+    match directive(comment)?.strip_prefix("kind=")?.trim() {
+        "mcf" => Some(true),
+        "mcrl2" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns true iff the input looks like a modal formula, based on its first token after the comments.
+pub fn sniff_mcf(input: &str) -> bool {
+    let mut rest = input;
+    loop {
+        rest = rest.trim_start();
+        match rest.strip_prefix('%') {
+            Some(comment) => rest = comment.find('\n').map_or("", |index| &comment[index..]),
+            None => break,
+        }
+    }
+
+    let word: String = rest
+        .chars()
+        .take_while(|ch| ch.is_alphanumeric() || *ch == '_')
+        .collect();
+    if SPECIFICATION_KEYWORDS.contains(&word.as_str()) {
+        false
+    } else if word.is_empty() {
+        FORMULA_KEYWORDS.iter().any(|token| rest.starts_with(token))
+    } else {
+        FORMULA_KEYWORDS.contains(&word.as_str())
+    }
+}
+
+/// Returns all files in the given paths, where directories are searched recursively for the files included by the configuration.
+pub fn collect_files(paths: &[String], config: &Config) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut result = Vec::new();
+
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            collect_directory(path, config, &mut result)?;
+        } else if path.exists() {
+            result.push(path.to_path_buf());
+        } else {
+            return Err(format!("Cannot find file {}", path.display()).into());
+        }
+    }
+
+    Ok(result)
+}
+
+/// Returns the files that are included by the configuration and, when paths are given, are contained in one of them.
+///
+/// This is used to select the files to check from the changed files of a repository.
+pub fn select_files(files: Vec<PathBuf>, paths: &[String], config: &Config) -> Vec<PathBuf> {
+    let paths: Vec<PathBuf> = paths.iter().filter_map(|path| fs::canonicalize(path).ok()).collect();
+
+    files
+        .into_iter()
+        .filter(|file| config.is_included(file))
+        .filter(|file| {
+            paths.is_empty()
+                || fs::canonicalize(file).is_ok_and(|file| paths.iter().any(|path| file.starts_with(path)))
+        })
+        .collect()
+}
+
+/// Adds all included files in the given directory and its subdirectories, in a deterministic order.
+fn collect_directory(directory: &Path, config: &Config, result: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            collect_directory(&entry, config, result)?;
+        } else if config.is_included(&entry) {
+            result.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_directive() {
+        assert_eq!(kind_directive("% parse-checker: kind=mcf\nnu X. X"), Some(true));
+        assert_eq!(kind_directive("%parse-checker:kind=mcrl2\ninit delta;"), Some(false));
+        assert_eq!(kind_directive("% Some comment\n% parse-checker: kind=mcf"), None);
+    }
+
+    #[test]
+    fn test_sniff_mcf() {
+        assert!(sniff_mcf("% The deadlock freedom property\n[true*]<true>true"));
+        assert!(sniff_mcf("nu X. [a]X"));
+        assert!(sniff_mcf("form mu X. <a>X;"));
+        assert!(!sniff_mcf("% A specification\nact a;\ninit a;"));
+        assert!(!sniff_mcf("proc P = a . P;"));
+    }
+
+    #[test]
+    fn test_select_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = |name: &str| directory.path().join(name);
+        fs::create_dir(path("properties")).unwrap();
+        for name in ["properties/deadlock.mcf", "spec.mcrl2", "notes.txt"] {
+            fs::write(path(name), "").unwrap();
+        }
+
+        let files = vec![path("properties/deadlock.mcf"), path("spec.mcrl2"), path("notes.txt")];
+        assert_eq!(
+            select_files(files.clone(), &[], &Config::default()),
+            vec![path("properties/deadlock.mcf"), path("spec.mcrl2")]
+        );
+
+        let properties = path("properties").to_string_lossy().to_string();
+        assert_eq!(
+            select_files(files, &[properties], &Config::default()),
+            vec![path("properties/deadlock.mcf")]
+        );
+    }
+
+    #[test]
+    fn test_is_mcf() {
+        assert!(is_mcf(Path::new("property.txt"), "<true>true", false));
+        assert!(!is_mcf(Path::new("property.mcrl2"), "<true>true", false));
+        assert!(is_mcf(Path::new("spec.txt"), "init delta;", true));
+    }
+}
@@ -0,0 +1,192 @@
+//! Lists the changed files using the local `git` binary.
+
+use std::env;
+use std::error::Error;
+use std::path::Path;
+use std::path::PathBuf;
+
+use duct::cmd;
+
+/// Returns the files that are changed since the given revision, including the changes that are not yet committed.
+///
+/// New files that are not yet added to git are also included, unless they are ignored.
+pub fn changed_since(revision: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    changed_since_in(&env::current_dir()?, revision)
+}
+
+/// Returns the files with changes that are staged for the next commit.
+pub fn staged() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    changed_files(
+        &env::current_dir()?,
+        &["diff", "--cached", "--name-only", "-z", "--diff-filter=ACMR", "--"],
+    )
+}
+
+/// Returns the contents of the file that are staged for the next commit, which can differ from the working tree.
+pub fn staged_contents(path: &Path) -> Result<String, Box<dyn Error>> {
+    staged_contents_in(&env::current_dir()?, path)
+}
+
+/// Returns the files that are changed since the revision in the repository of the given directory, see [changed_since].
+fn changed_since_in(directory: &Path, revision: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files = changed_files(
+        directory,
+        &["diff", "--name-only", "-z", "--diff-filter=ACMR", revision, "--"],
+    )?;
+
+    // Untracked files are never listed by git diff, and the pathspec `:/` lists those of the whole repository.
+    for file in changed_files(
+        directory,
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--full-name",
+            "-z",
+            "--",
+            ":/",
+        ],
+    )? {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Returns the existing files listed by the given git command in the directory, relative to that directory when they
+/// are within it.
+fn changed_files(directory: &Path, arguments: &[&str]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    // Git prints the paths relative to the root of the repository.
+    let root = PathBuf::from(git(directory, &["rev-parse", "--show-toplevel"])?.trim_end());
+    let directory = directory.canonicalize()?;
+
+    Ok(git(&directory, arguments)?
+        .split('\0')
+        .filter(|name| !name.is_empty())
+        .map(|name| root.join(name))
+        .filter(|path| path.is_file())
+        .map(|path| path.strip_prefix(&directory).map_or(path.clone(), Path::to_path_buf))
+        .collect())
+}
+
+/// Returns the staged contents of the file, where a relative path is relative to the given directory.
+fn staged_contents_in(directory: &Path, path: &Path) -> Result<String, Box<dyn Error>> {
+    // The index is addressed by the path relative to the root of the repository, with forward slashes.
+    let root = PathBuf::from(git(directory, &["rev-parse", "--show-toplevel"])?.trim_end());
+    let absolute = directory.canonicalize()?.join(path);
+    let relative = absolute
+        .strip_prefix(&root)
+        .map_err(|_| format!("{} is not in the repository at {}", path.display(), root.display()))?;
+
+    let name = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    git(directory, &["show", &format!(":{}", name)])
+}
+
+/// Runs git with the given arguments in the directory, and returns its output.
+fn git(directory: &Path, arguments: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = cmd("git", arguments)
+        .dir(directory)
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()
+        .map_err(|e| format!("Cannot run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            arguments.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_staged_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let git = |arguments: &[&str]| git(directory.path(), arguments).unwrap();
+
+        git(&["init", "--quiet"]);
+        fs::create_dir(directory.path().join("properties")).unwrap();
+        fs::write(directory.path().join("properties/deadlock.mcf"), "[true*]<true>true").unwrap();
+        fs::write(directory.path().join("spec.mcrl2"), "init delta;").unwrap();
+        git(&["add", "properties/deadlock.mcf"]);
+
+        // Only the staged contents are checked, not the later changes in the working tree.
+        fs::write(directory.path().join("properties/deadlock.mcf"), "[true*]<a>true").unwrap();
+
+        let arguments = ["diff", "--cached", "--name-only", "-z", "--diff-filter=ACMR", "--"];
+        assert_eq!(
+            changed_files(directory.path(), &arguments).unwrap(),
+            vec![PathBuf::from("properties/deadlock.mcf")]
+        );
+        assert_eq!(
+            changed_files(&directory.path().join("properties"), &arguments).unwrap(),
+            vec![PathBuf::from("deadlock.mcf")]
+        );
+
+        assert_eq!(
+            staged_contents_in(directory.path(), Path::new("properties/deadlock.mcf")).unwrap(),
+            "[true*]<true>true"
+        );
+        assert_eq!(
+            staged_contents_in(&directory.path().join("properties"), Path::new("deadlock.mcf")).unwrap(),
+            "[true*]<true>true"
+        );
+        assert!(staged_contents_in(directory.path(), Path::new("spec.mcrl2")).is_err());
+    }
+
+    #[test]
+    fn test_changed_since() {
+        let directory = tempfile::tempdir().unwrap();
+        let git = |arguments: &[&str]| git(directory.path(), arguments).unwrap();
+
+        git(&["init", "--quiet"]);
+        fs::create_dir(directory.path().join("properties")).unwrap();
+        fs::write(directory.path().join("properties/deadlock.mcf"), "[true*]<true>true").unwrap();
+        fs::write(directory.path().join("spec.mcrl2"), "init delta;").unwrap();
+        fs::write(directory.path().join(".gitignore"), "*.log\n").unwrap();
+        git(&["add", "."]);
+        git(&[
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            "initial",
+        ]);
+
+        // A modified file, an untracked file in a subdirectory and an ignored file.
+        fs::write(directory.path().join("spec.mcrl2"), "init tau;").unwrap();
+        fs::write(directory.path().join("properties/livelock.mcf"), "[true*]<true>true").unwrap();
+        fs::write(directory.path().join("check.log"), "").unwrap();
+
+        assert_eq!(
+            changed_since_in(directory.path(), "HEAD").unwrap(),
+            vec![PathBuf::from("spec.mcrl2"), PathBuf::from("properties/livelock.mcf")]
+        );
+
+        // The files of the whole repository are listed, where those outside the directory keep an absolute path.
+        let properties = directory.path().join("properties");
+        let files = changed_since_in(&properties, "HEAD").unwrap();
+        assert!(files.contains(&PathBuf::from("livelock.mcf")));
+        assert!(files.iter().any(|file| file.ends_with("spec.mcrl2")));
+    }
+}
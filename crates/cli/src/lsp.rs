@@ -0,0 +1,407 @@
+//! A language server that reports the parsing differences while editing.
+//!
+//! The server communicates over stdio, publishes a diagnostic for every
+//! statement that is parsed differently by both releases, shows both groupings
+//! on hover and offers code actions that insert the parentheses of either release
+//! into the statement.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::ops::Range;
+use std::path::Path;
+
+use lsp_server::Connection;
+use lsp_server::ErrorCode;
+use lsp_server::Message;
+use lsp_server::Notification;
+use lsp_server::Request;
+use lsp_server::Response;
+use lsp_types::CodeAction;
+use lsp_types::CodeActionKind;
+use lsp_types::CodeActionOrCommand;
+use lsp_types::CodeActionParams;
+use lsp_types::CodeActionProviderCapability;
+use lsp_types::Diagnostic;
+use lsp_types::DiagnosticSeverity;
+use lsp_types::DidChangeTextDocumentParams;
+use lsp_types::DidCloseTextDocumentParams;
+use lsp_types::DidOpenTextDocumentParams;
+use lsp_types::Hover;
+use lsp_types::HoverContents;
+use lsp_types::HoverParams;
+use lsp_types::HoverProviderCapability;
+use lsp_types::MarkupContent;
+use lsp_types::MarkupKind;
+use lsp_types::NumberOrString;
+use lsp_types::Position;
+use lsp_types::PublishDiagnosticsParams;
+use lsp_types::ServerCapabilities;
+use lsp_types::TextDocumentSyncCapability;
+use lsp_types::TextDocumentSyncKind;
+use lsp_types::TextEdit;
+use lsp_types::WorkspaceEdit;
+use lsp_types::notification::DidChangeTextDocument;
+use lsp_types::notification::DidCloseTextDocument;
+use lsp_types::notification::DidOpenTextDocument;
+use lsp_types::notification::Notification as _;
+use lsp_types::notification::PublishDiagnostics;
+use lsp_types::request::CodeActionRequest;
+use lsp_types::request::HoverRequest;
+use lsp_types::request::Request as _;
+
+use crate::Checker;
+use crate::Config;
+use crate::InputKind;
+use crate::Rule;
+use crate::insert_parentheses;
+
+/// The name of the server in diagnostics.
+const SOURCE: &str = "parse-checker";
+
+/// A statement that is parsed differently by both releases.
+struct Finding {
+    /// The byte range of the statement in the document, which is matched to the statement in the ASTs.
+    range: Range<usize>,
+
+    /// The rule that caused the difference.
+    rule: Rule,
+
+    /// The statement as parsed by the previous release.
+    previous: String,
+
+    /// The statement as parsed by the current release.
+    current: String,
+}
+
+/// An open document with the differences of its last version.
+struct Document {
+    text: String,
+    findings: Vec<Finding>,
+}
+
+/// Runs the language server on stdio until the client shuts it down, where all documents are checked with the checker.
+pub fn run_server(checker: &Checker, config: &Config) -> Result<(), Box<dyn Error>> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    // The documents are stored by the text of their URI, since [lsp_types::Uri] is not a good key for maps.
+    let mut documents: HashMap<String, Document> = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+
+                // Invalid requests are answered with an error, instead of stopping the server.
+                let response = handle_request(&documents, checker, &request).unwrap_or_else(|error| {
+                    Response::new_err(request.id.clone(), ErrorCode::InvalidParams as i32, error.to_string())
+                });
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                match handle_notification(&mut documents, checker, config, notification) {
+                    Ok(Some(diagnostics)) => {
+                        connection.sender.send(Message::Notification(Notification::new(
+                            PublishDiagnostics::METHOD.to_string(),
+                            diagnostics,
+                        )))?;
+                    }
+                    Ok(None) => {}
+                    Err(error) => eprintln!("Error: ignoring an invalid notification: {}", error),
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    // The writer thread stops when the connection is dropped.
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Updates the documents, and returns the diagnostics that must be published for the changed document.
+fn handle_notification(
+    documents: &mut HashMap<String, Document>,
+    checker: &Checker,
+    config: &Config,
+    notification: Notification,
+) -> Result<Option<PublishDiagnosticsParams>, Box<dyn Error>> {
+    let (uri, text) = match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            (params.text_document.uri, params.text_document.text)
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+
+            // The documents are synchronised in full, so the last change contains the whole text.
+            let Some(change) = params.content_changes.into_iter().last() else {
+                return Ok(None);
+            };
+            (params.text_document.uri, change.text)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            documents.remove(params.text_document.uri.as_str());
+
+            return Ok(Some(PublishDiagnosticsParams {
+                uri: params.text_document.uri,
+                diagnostics: Vec::new(),
+                version: None,
+            }));
+        }
+        _ => return Ok(None),
+    };
+
+    let mcf = config.is_mcf(Path::new(uri.path().as_str()), &text, false);
+    let (findings, diagnostics) = analyse(checker, &text, mcf);
+    documents.insert(uri.to_string(), Document { text, findings });
+
+    Ok(Some(PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    }))
+}
+
+/// Parses the text with both releases, and returns the differences and the diagnostics that describe them.
+fn analyse(checker: &Checker, text: &str, mcf: bool) -> (Vec<Finding>, Vec<Diagnostic>) {
+    let comparison = match checker.compare(text, InputKind::from_mcf(mcf)) {
+        Ok(comparison) => comparison,
+        Err(error) => {
+            let diagnostic = Diagnostic {
+                range: lsp_types::Range::default(),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some(SOURCE.to_string()),
+                message: error.to_string(),
+                ..Default::default()
+            };
+            return (Vec::new(), vec![diagnostic]);
+        }
+    };
+
+    let differences: Vec<_> = comparison
+        .differences(text)
+        .into_iter()
+        .filter(|difference| !difference.is_suppressed())
+        .collect();
+
+    // Only the differences whose statement is matched to the ASTs can be located in the document.
+    let findings: Vec<Finding> = differences
+        .iter()
+        .filter_map(|difference| {
+            Some(Finding {
+                range: difference.original.as_ref()?.range.clone(),
+                rule: difference.rule(),
+                previous: difference.previous.to_string(),
+                current: difference.current.to_string(),
+            })
+        })
+        .collect();
+
+    let mut diagnostics: Vec<Diagnostic> = findings
+        .iter()
+        .map(|finding| Diagnostic {
+            range: to_range(text, &finding.range),
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(finding.rule.id().to_string())),
+            source: Some(SOURCE.to_string()),
+            message: format!(
+                "This is parsed differently by mCRL2 {} and {}",
+                checker.previous().version(),
+                checker.current().version()
+            ),
+            ..Default::default()
+        })
+        .collect();
+
+    let unmatched = differences.len() - findings.len();
+    if unmatched > 0 {
+        diagnostics.push(Diagnostic {
+            range: lsp_types::Range::default(),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some(SOURCE.to_string()),
+            message: format!(
+                "{} statement(s) are parsed differently, but cannot be located in the document",
+                unmatched
+            ),
+            ..Default::default()
+        });
+    }
+
+    for statement in comparison.unattached_directives(text) {
+        diagnostics.push(Diagnostic {
+            range: to_range(text, &statement.range),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some(SOURCE.to_string()),
+            message: "The directive cannot be attached to a statement of the AST, so it has no effect".to_string(),
+            ..Default::default()
+        });
+    }
+
+    (findings, diagnostics)
+}
+
+/// Answers a hover or code action request.
+fn handle_request(
+    documents: &HashMap<String, Document>,
+    checker: &Checker,
+    request: &Request,
+) -> Result<Response, Box<dyn Error>> {
+    let previous = checker.previous().version();
+    let current = checker.current().version();
+
+    match request.method.as_str() {
+        HoverRequest::METHOD => {
+            let params: HoverParams = serde_json::from_value(request.params.clone())?;
+            let position = params.text_document_position_params;
+
+            let hover = documents.get(position.text_document.uri.as_str()).and_then(|document| {
+                let offset = to_offset(&document.text, position.position);
+                let finding = document
+                    .findings
+                    .iter()
+                    .find(|finding| finding.range.contains(&offset) || finding.range.end == offset)?;
+
+                Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: format!(
+                            "Parsed differently (rule `{}`)\n\nGrouping in mCRL2 {}:\n```\n{}\n```\n\nGrouping in mCRL2 {}:\n```\n{}\n```",
+                            finding.rule.id(),
+                            previous,
+                            finding.previous,
+                            current,
+                            finding.current
+                        ),
+                    }),
+                    range: Some(to_range(&document.text, &finding.range)),
+                })
+            });
+
+            Ok(Response::new_ok(request.id.clone(), hover))
+        }
+        CodeActionRequest::METHOD => {
+            let params: CodeActionParams = serde_json::from_value(request.params.clone())?;
+            let uri = params.text_document.uri;
+
+            let mut actions = Vec::new();
+            if let Some(document) = documents.get(uri.as_str()) {
+                let start = to_offset(&document.text, params.range.start);
+                let end = to_offset(&document.text, params.range.end);
+
+                for finding in &document.findings {
+                    if finding.range.start > end || finding.range.end < start {
+                        continue;
+                    }
+
+                    // The inserted parentheses make the statement parse the same by both releases.
+                    let statement = &document.text[finding.range.clone()];
+                    for (release, grouping) in [(previous, &finding.previous), (current, &finding.current)] {
+                        let Some(insertions) = insert_parentheses(statement, grouping) else {
+                            continue;
+                        };
+
+                        let edits = insertions
+                            .into_iter()
+                            .map(|(offset, parentheses)| {
+                                let position = to_position(&document.text, finding.range.start + offset);
+                                TextEdit {
+                                    range: lsp_types::Range {
+                                        start: position,
+                                        end: position,
+                                    },
+                                    new_text: parentheses,
+                                }
+                            })
+                            .collect();
+
+                        actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                            title: format!("Insert parentheses to keep the mCRL2 {} meaning", release),
+                            kind: Some(CodeActionKind::QUICKFIX),
+                            edit: Some(WorkspaceEdit {
+                                changes: Some(HashMap::from([(uri.clone(), edits)])),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        }));
+                    }
+                }
+            }
+
+            Ok(Response::new_ok(request.id.clone(), actions))
+        }
+        method => Ok(Response::new_err(
+            request.id.clone(),
+            ErrorCode::MethodNotFound as i32,
+            format!("Unsupported request {}", method),
+        )),
+    }
+}
+
+/// Returns the position of the byte offset in the text, where the character is counted in UTF-16 code units.
+fn to_position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// Returns the range of positions of the byte range in the text.
+fn to_range(text: &str, range: &Range<usize>) -> lsp_types::Range {
+    lsp_types::Range {
+        start: to_position(text, range.start),
+        end: to_position(text, range.end),
+    }
+}
+
+/// Returns the byte offset of the position in the text, which is clamped to the end of its line.
+fn to_offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+
+    let line = &text[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+
+    let mut units = 0;
+    for (index, ch) in line.char_indices() {
+        if units >= position.character as usize {
+            return line_start + index;
+        }
+        units += ch.len_utf16();
+    }
+
+    line_start + line.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions() {
+        let text = "act a;\ninit \u{1d49c} && b;\n";
+        let offset = text.find("&&").unwrap();
+
+        let position = to_position(text, offset);
+        assert_eq!(position, Position { line: 1, character: 8 });
+        assert_eq!(to_offset(text, position), offset);
+        assert_eq!(to_offset(text, Position { line: 0, character: 100 }), 6);
+    }
+}
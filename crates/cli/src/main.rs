@@ -2,137 +2,729 @@
 
 #![forbid(unsafe_code)]
 
-use std::fmt::Write;
 use std::fs;
+use std::io::IsTerminal;
+use std::io::Read;
+use std::io::stdin;
+use std::io::stdout;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::ExitCode;
 use std::error::Error;
 
+use clap::ArgGroup;
+use clap::Args;
 use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use serde::Deserialize;
 
 // Import the other modules.
 mod test_examples;
-mod diff;
+mod baseline;
+mod config;
+mod files;
+mod git;
+mod lsp;
+mod matrix;
+mod project;
+mod repl;
+mod report;
+mod scripts;
+mod serve;
+mod snippets;
+mod watch;
 
-pub use diff::*;
+pub use parse_checker_core::*;
+pub use baseline::*;
+pub use config::*;
+pub use files::*;
+pub use project::*;
+pub use scripts::*;
+pub use snippets::*;
+
+/// The exit code when the releases parse an input differently.
+const EXIT_DIFFERENCE: u8 = 1;
+
+/// The exit code when one of the releases cannot parse an input, or a property cannot be type checked.
+const EXIT_PARSE_ERROR: u8 = 3;
+
+/// The exit code when an input could not be checked, for example because the mcrl2-2024 helper cannot be run.
+const EXIT_INTERNAL_ERROR: u8 = 4;
+
+/// Describes the exit codes in the help message.
+const EXIT_CODES_HELP: &str = "Exit codes:
+  0  All inputs parse identically, or none of the outcomes is selected by --fail-on.
+  1  The releases parse an input differently.
+  2  The command line arguments are invalid.
+  3  One of the releases cannot parse an input, or a property cannot be type checked.
+  4  An input could not be checked, for example because the mcrl2-2024 helper cannot be run.
+
+When multiple inputs are checked the exit code of the most severe outcome is used.";
 
 #[derive(Parser)]
-#[command(version, 
+#[command(version,
     name = "parse-checker",
     author = "Maurice Laveaux",
-    about = "A tool that can be used to check whether mCRL2 specifications or modal formulas parse differently between the 202407.1 and 202507.0 release.")]
+    about = "A tool that can be used to check whether mCRL2 specifications or modal formulas parse differently between the 202407.1 and 202507.0 release.",
+    after_help = EXIT_CODES_HELP,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    group = ArgGroup::new("source").required(true).args(["input", "expr"]))]
 struct Cli {
-    /// The path for the file to check, 
-    input: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The path for the file to check, or `-` to read the input from stdin.
+    input: Option<String>,
 
-    /// Whether to check mCRL2 specifications (default) or modal formulas.
+    /// Checks the given text instead of a file, for example `--expr '[true*]<true>true'`.
+    #[arg(long, value_name = "TEXT")]
+    expr: Option<String>,
+
+    /// The file name that is used in messages and to determine the kind of an input that is read from stdin or given by --expr.
+    #[arg(long, value_name = "NAME")]
+    stdin_filename: Option<PathBuf>,
+
+    /// Whether to check modal formulas, otherwise the kind of files without a .mcf or .mcrl2 extension is determined from their contents.
     #[arg(long)]
     mcf: bool,
 
-    /// Prints the parse tree of the input file.
+    /// Type checks the properties of an mCRL2 IDE project (.mcrl2proj) against the specification of the project.
+    #[arg(long)]
+    type_check: bool,
+
+    /// Prints the parse tree of the input file using the --to backend, which is the 202507.0 release by default.
     #[arg(long)]
     print: bool,
 
-    /// Prints the parse tree of the input file using the 2024 release of mCRL2.
+    /// Prints the parse tree of the input file using the --from backend, which is the 202407.1 release by default.
     #[arg(long)]
     print_2024: bool,
 
-    /// Prints the parse tree indented (whenever it is printed).
+    #[command(flatten)]
+    backends: BackendArgs,
+
+    /// Compares every pair of registered backends, and prints a table with their outcomes.
+    #[arg(long, conflicts_with_all = ["from", "to", "print", "print_2024"])]
+    matrix: bool,
+
+    /// Prints the parse tree indented (whenever it is printed), which is implied by --width and --indent-width.
     #[arg(short, long)]
     indented: bool,
+
+    /// The maximum line width of the indented parse tree [default: 100].
+    #[arg(long)]
+    width: Option<usize>,
+
+    /// The number of spaces used for every level of indentation in the indented parse tree [default: 2].
+    #[arg(long)]
+    indent_width: Option<usize>,
+
+    #[command(flatten)]
+    diff: DiffArgs,
+
+    #[command(flatten)]
+    policy: PolicyArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Reports the parsing differences of all files in the given paths.
+    #[command(after_help = EXIT_CODES_HELP)]
+    Report(ReportArgs),
+
+    /// Prints a canonical, fully parenthesized and formatted AST, which can be used as a git textconv diff driver.
+    ///
+    /// Configure it with `git config diff.mcrl2.textconv "parse-checker textconv"` and `*.mcf diff=mcrl2` in
+    /// .gitattributes, so that `git diff` only shows changes to the grouping and not to the formatting.
+    Textconv(TextconvArgs),
+
+    /// Runs a language server over stdio that reports the parsing differences while editing.
+    ///
+    /// It publishes diagnostics for the statements that are parsed differently, shows both groupings on
+    /// hover and offers code actions that insert the parentheses needed to keep the meaning of either release.
+    Lsp(LspArgs),
+
+    /// Starts an interactive shell that prints how both releases parse every entered line.
+    ///
+    /// The lines are parsed as modal formulas, mCRL2 specifications or data expressions, and both ASTs are printed
+    /// next to each other with the part in which they differ highlighted. Enter `:help` for the available commands.
+    Repl(ReplArgs),
+
+    /// Answers comparison requests with a JSON-over-HTTP API on localhost.
+    ///
+    /// A `POST /compare` request with a body such as `{"text": "[true*]<true>true", "kind": "mcf"}` returns the
+    /// outcome, the ASTs of both releases and the parse errors. The requests are answered one at a time.
+    Serve(ServeArgs),
+
+    /// Checks all files in the given paths, and checks them again whenever they change until it is stopped.
+    ///
+    /// Only new results are printed, i.e., those that differ from the previous result of the same file.
+    Watch(WatchArgs),
+}
+
+#[derive(Args)]
+struct WatchArgs {
+    /// The files or directories to watch, directories are searched recursively like the report subcommand does.
+    #[arg(required = true)]
+    paths: Vec<String>,
+
+    /// Whether files without a .mcf or .mcrl2 extension are modal formulas, otherwise their kind is determined from their contents.
+    #[arg(long)]
+    mcf: bool,
+
+    #[command(flatten)]
+    backends: BackendArgs,
+
+    #[command(flatten)]
+    diff: DiffArgs,
+}
+
+#[derive(Args)]
+struct LspArgs {
+    #[command(flatten)]
+    backends: BackendArgs,
+}
+
+#[derive(Args)]
+struct ReplArgs {
+    #[command(flatten)]
+    diff: DiffArgs,
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    /// The port on localhost to listen on.
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+}
+
+#[derive(Args)]
+struct TextconvArgs {
+    /// The file to convert, which git passes as argument.
+    path: PathBuf,
+
+    /// The release of mCRL2 whose parser is used.
+    #[arg(long, value_enum, default_value_t = Release::Current)]
+    release: Release,
+
+    /// Whether files without a .mcf or .mcrl2 extension are modal formulas, otherwise their kind is determined from their contents.
+    #[arg(long)]
+    mcf: bool,
+
+    /// The maximum line width of the AST.
+    #[arg(long, default_value_t = Layout::default().width)]
+    width: usize,
+
+    /// The number of spaces used for every level of indentation in the AST.
+    #[arg(long, default_value_t = Layout::default().indent)]
+    indent_width: usize,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Release {
+    /// The 202407.1 release, before the parser changes.
+    #[value(name = "202407.1")]
+    Previous,
+
+    /// The 202507.0 release, after the parser changes.
+    #[value(name = "202507.0")]
+    Current,
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    /// The files or directories to check, directories are searched recursively for .mcf, .mcrl2 and document files (or the configured include patterns), and for shell scripts and Makefiles the files they reference are checked.
+    ///
+    /// With --changed-since or --staged only the changed files within these paths are checked.
+    #[arg(required_unless_present_any = ["changed_since", "staged"])]
+    paths: Vec<String>,
+
+    /// Only checks the files that changed since the given git revision, including uncommitted changes and untracked files.
+    #[arg(long, value_name = "REV")]
+    changed_since: Option<String>,
+
+    /// Only checks the files with changes that are staged for the next commit, which is useful in a pre-commit hook.
+    ///
+    /// Their staged contents are checked, so changes in the working tree that are not staged are ignored.
+    #[arg(long, conflicts_with = "changed_since")]
+    staged: bool,
+
+    /// Whether files without a .mcf or .mcrl2 extension are modal formulas, otherwise their kind is determined from their contents.
+    #[arg(long)]
+    mcf: bool,
+
+    /// Type checks the properties of mCRL2 IDE projects (.mcrl2proj) against the specification of the project.
+    #[arg(long)]
+    type_check: bool,
+
+    /// The format of the report, which defaults to the format in the configuration file or plain text.
+    #[arg(long, value_enum)]
+    format: Option<ReportFormat>,
+
+    /// Writes a migration report in Markdown, which can be used in a pull request description or wiki page.
+    #[arg(long, conflicts_with = "format")]
+    markdown: bool,
+
+    /// Only reports the differences that are not accepted by the given baseline file.
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Writes all differences to the given baseline file, so that they are accepted in later runs.
+    #[arg(long, value_name = "FILE", conflicts_with = "baseline")]
+    write_baseline: Option<PathBuf>,
+
+    #[command(flatten)]
+    backends: BackendArgs,
+
+    /// Compares every pair of registered backends for every file, and prints a table with their outcomes.
+    #[arg(long, conflicts_with_all = ["from", "to", "format", "markdown", "baseline", "write_baseline"])]
+    matrix: bool,
+
+    #[command(flatten)]
+    diff: DiffArgs,
+
+    #[command(flatten)]
+    policy: PolicyArgs,
+}
+
+#[derive(Clone, Copy, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportFormat {
+    /// Lists the outcome and differences of every file.
+    Text,
+
+    /// Writes a migration report in Markdown.
+    Markdown,
+}
+
+#[derive(Args)]
+struct BackendArgs {
+    /// The backend whose AST is compared as the previous release, see --matrix for the registered backends.
+    #[arg(long, value_name = "NAME", default_value = PREVIOUS_RELEASE)]
+    from: String,
+
+    /// The backend whose AST is compared as the current release.
+    #[arg(long, value_name = "NAME", default_value = CURRENT_RELEASE)]
+    to: String,
+
+    /// Registers a backend executable with the interface of the mcrl2-2024 helper, as NAME=PATH or NAME@VERSION=PATH.
+    ///
+    /// It reads the input from stdin, parses a modal formula when --mcf is given and prints the AST. Can be repeated.
+    #[arg(long, value_name = "NAME=PATH")]
+    backend: Vec<ExternalBackend>,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// The layout in which the differences between both releases are printed [default: side-by-side].
+    #[arg(long, value_enum)]
+    diff: Option<DiffLayout>,
+
+    /// When to colour the output, where `auto` only colours terminal output if NO_COLOR is not set.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+}
+
+#[derive(Clone, Copy, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffLayout {
+    /// Prints both releases next to each other in two columns.
+    SideBySide,
+
+    /// Prints a unified line diff.
+    Unified,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Args)]
+struct PolicyArgs {
+    /// The outcomes that result in a non-zero exit code, where `none` never fails [default: difference,parse-error,internal-error].
+    #[arg(long, value_enum, value_delimiter = ',')]
+    fail_on: Option<Vec<FailOn>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailOn {
+    /// The releases parse an input differently.
+    Difference,
+
+    /// One of the releases cannot parse an input.
+    ParseError,
+
+    /// An input could not be checked.
+    InternalError,
+
+    /// No outcome results in a failure.
+    None,
 }
 
-fn main() -> Result<ExitCode, Box<dyn Error>> {
-    let mut cli = Cli::parse();
+impl PolicyArgs {
+    /// Returns the exit code of the most severe outcome that counts as a failure.
+    fn exit_code(&self, config: &Config, outcomes: &[Outcome]) -> ExitCode {
+        let fail_on = self
+            .fail_on
+            .as_deref()
+            .or(config.fail_on.as_deref())
+            .unwrap_or(&[FailOn::Difference, FailOn::ParseError, FailOn::InternalError]);
 
-    let input_path = Path::new(&cli.input);
-    if !input_path.exists() {
-        return Err(format!("Cannot find file {}", cli.input))?;
+        let failure = outcomes
+            .iter()
+            .filter(|outcome| {
+                fail_on.iter().any(|fail_on| match fail_on {
+                    FailOn::Difference => **outcome == Outcome::Difference,
+                    FailOn::ParseError => **outcome == Outcome::ParseError,
+                    FailOn::InternalError => **outcome == Outcome::InternalError,
+                    FailOn::None => false,
+                })
+            })
+            .max();
+
+        match failure {
+            None | Some(Outcome::Identical) => ExitCode::SUCCESS,
+            Some(Outcome::Difference) => ExitCode::from(EXIT_DIFFERENCE),
+            Some(Outcome::ParseError) => ExitCode::from(EXIT_PARSE_ERROR),
+            Some(Outcome::InternalError) => ExitCode::from(EXIT_INTERNAL_ERROR),
+        }
+    }
+}
+
+impl BackendArgs {
+    /// Returns the registry with the configured backends and those given by --backend.
+    fn registry(&self, config: &Config) -> Result<Registry, Box<dyn Error>> {
+        config.registry(&self.backend)
+    }
+
+    /// Returns the checker that compares the backends selected by --from and --to.
+    fn checker(&self, config: &Config) -> Result<Checker, Box<dyn Error>> {
+        self.registry(config)?.checker(&self.from, &self.to)
+    }
+}
+
+impl DiffArgs {
+    /// Returns the options for printing differences to the standard output.
+    fn options(&self, config: &Config) -> DiffOptions {
+        let color = match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && stdout().is_terminal()
+            }
+        };
+
+        let width = console::Term::stdout()
+            .size_checked()
+            .map_or(DiffOptions::default().width, |(_rows, columns)| columns as usize);
+
+        DiffOptions {
+            format: match self.diff.or(config.diff) {
+                Some(DiffLayout::SideBySide) | None => DiffFormat::SideBySide,
+                Some(DiffLayout::Unified) => DiffFormat::Unified,
+            },
+            color,
+            width,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let config = match Config::find() {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            return ExitCode::from(EXIT_INTERNAL_ERROR);
+        }
+    };
+
+    let outcomes = match run(&cli, &config) {
+        Ok(outcomes) => outcomes,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            vec![Outcome::of_error(error.as_ref())]
+        }
+    };
+
+    match &cli.command {
+        Some(Command::Report(args)) => args.policy.exit_code(&config, &outcomes),
+        _ => cli.policy.exit_code(&config, &outcomes),
     }
+}
+
+/// Runs the tool, and returns the outcome of every checked input.
+fn run(cli: &Cli, config: &Config) -> Result<Vec<Outcome>, Box<dyn Error>> {
+    match &cli.command {
+        Some(Command::Report(args)) => return run_report(args, config),
+        Some(Command::Textconv(args)) => return run_textconv(&config.checker(), args, config),
+        Some(Command::Lsp(args)) => {
+            lsp::run_server(&args.backends.checker(config)?, config)?;
+            return Ok(Vec::new());
+        }
+        Some(Command::Repl(args)) => {
+            repl::run_repl(&config.checker(), &args.diff.options(config))?;
+            return Ok(Vec::new());
+        }
+        Some(Command::Serve(args)) => {
+            serve::serve(&config.checker(), args.port)?;
+            return Ok(Vec::new());
+        }
+        Some(Command::Watch(args)) => {
+            let checker = args.backends.checker(config)?;
+            watch::watch(&checker, &args.paths, args.mcf, config, &args.diff.options(config))?;
+            return Ok(Vec::new());
+        }
+        None => {}
+    }
+
+    let (input_path, input) = read_input(cli)?;
+    let registry = cli.backends.registry(config)?;
+    let checker = registry.checker(&cli.backends.from, &cli.backends.to)?;
+
+    // Projects, scripts and documents consist of multiple inputs, which are reported like the report subcommand does.
+    if !(cli.print || cli.print_2024) {
+        let reports = if is_project(&input_path) {
+            Some(report::check_project(&checker, &input_path, cli.type_check)?)
+        } else if is_script(&input_path) {
+            Some(report::check_script(&checker, &input_path, &input, config)?)
+        } else if is_document(&input_path) {
+            Some(report::check_document(&checker, &input_path, &input))
+        } else {
+            None
+        };
+
+        if let Some(reports) = reports {
+            if cli.matrix {
+                return run_report_matrix(&registry, &reports);
+            }
 
-    if let Some(ext) = input_path.extension() {
-        // Detect input format, otherwise use the as specificed by the user.
-        if  ext == "mcf" {
-            // If the file has a .mcf extension, we assume it's a modal formula. This is also what the toolset does.
-            cli.mcf = true;
-        } else if ext == "mcrl2" {
-            cli.mcf = false;
+            report::write_text(&mut stdout(), &cli.diff.options(config), &reports)?;
+            return Ok(reports.iter().map(|report| report.outcome()).collect());
         }
     }
 
-    let input = fs::read_to_string(input_path)?;
+    // Detect input format, otherwise use the as specificed by the user.
+    let mcf = config.is_mcf(&input_path, &input, cli.mcf);
 
     if cli.print || cli.print_2024 {
-        let ast = print(&cli, &input).map_err(|e| format!("Error: {}", e))?;
+        let ast = print(cli, &checker, mcf, &input)?;
 
-        if cli.indented {
-            print!("{}", PrintIndented(&ast));
+        if cli.indented || cli.width.is_some() || cli.indent_width.is_some() {
+            let layout = Layout {
+                width: cli.width.unwrap_or(Layout::default().width),
+                indent: cli.indent_width.unwrap_or(Layout::default().indent),
+            };
+            print!("{}", layout.format(&ast));
         } else {
             print!("{}", ast);
         }
 
-        return Ok(ExitCode::SUCCESS);
+        return Ok(Vec::new());
     }
 
-    
-    if cli.mcf {
-        diff_mcf(&input)?;
-    } else {
-        // Default to checking mCRL2 specifications
-        diff_mcrl2(&input)?;
+    if cli.matrix {
+        return matrix::run_matrix(&mut stdout(), &registry, &input_path, &input, InputKind::from_mcf(mcf));
     }
 
-    Ok(ExitCode::SUCCESS)
+    let outcome = diff(
+        &checker,
+        &input_path,
+        &input,
+        InputKind::from_mcf(mcf),
+        &cli.diff.options(config),
+    )?;
+
+    Ok(vec![outcome])
 }
 
-fn print(cli: &Cli, input: &str) -> Result<String, Box<dyn Error>> {    
-    // If the user wants to print the parse tree, print it depending on the specified options.
-    if cli.print {
-        if cli.mcf {
-            Ok(mcrl2_sys::print_ast_mcf(&input)?)
-        } else {
-            Ok(mcrl2_sys::print_ast_mcrl2(&input)?)
+/// Prints the table of [matrix::run_matrix] for the input of every report, and returns the outcomes of all tables.
+fn run_report_matrix(registry: &Registry, reports: &[report::FileReport]) -> Result<Vec<Outcome>, Box<dyn Error>> {
+    let mut outcomes = Vec::new();
+    for (index, report) in reports.iter().enumerate() {
+        if index > 0 {
+            println!();
         }
-    } else {
-        print_ast_2024(&input, cli.mcf)
+
+        println!("{}:", report.location(None));
+        outcomes.extend(matrix::run_matrix(
+            &mut stdout(),
+            registry,
+            &report.path,
+            &report.input,
+            InputKind::from_mcf(report.mcf),
+        )?);
     }
+
+    Ok(outcomes)
 }
 
-struct PrintIndented<'a>(&'a str);
+/// Compares the ASTs of the input, and prints the differences to stdout.
+///
+/// Parse errors are printed to stderr, where the path of the input is only used in messages.
+fn diff(
+    checker: &Checker,
+    path: &Path,
+    input: &str,
+    kind: InputKind,
+    options: &DiffOptions,
+) -> Result<Outcome, Box<dyn Error>> {
+    let comparison = match checker.compare(input, kind) {
+        Ok(comparison) => comparison,
+        Err(error) if error.is::<ParseError>() => {
+            eprintln!("{}: {}", path.display(), error);
+            return Ok(Outcome::ParseError);
+        }
+        Err(error) => return Err(error),
+    };
 
-impl std::fmt::Display for PrintIndented< '_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        print_indented(f, self.0)
+    for statement in comparison.unattached_directives(input) {
+        eprintln!(
+            "Warning: {}:{}: the directive cannot be attached to a statement of the AST, so it has no effect",
+            path.display(),
+            statement.line(input)
+        );
     }
+
+    if comparison.outcome(input) == Outcome::Identical {
+        return Ok(Outcome::Identical);
+    }
+
+    let labels = (
+        format!("mCRL2 {}", checker.previous().version()),
+        format!("mCRL2 {}", checker.current().version()),
+    );
+    print_labelled_diff(
+        &mut stdout(),
+        options,
+        (&labels.0, &labels.1),
+        &comparison.previous,
+        &comparison.current,
+    )?;
+    Ok(Outcome::Difference)
 }
 
-fn print_indented(writer: &mut impl Write, s: &str) -> Result<(), std::fmt::Error> {
-    let mut indent_level = 0;
-    let mut chars = s.chars().peekable();
-    
-    while let Some(ch) = chars.next() {
-        match ch {
-            '(' => {
-                write!(writer, "{}", ch)?;
-                indent_level += 1;
-                writeln!(writer)?;
-                write!(writer, "{}", "  ".repeat(indent_level))?;
-            }
-            ')' => {
-                if indent_level > 0 {
-                    indent_level -= 1;
-                }
-                writeln!(writer)?;
-                write!(writer, "{}{}", "  ".repeat(indent_level), ch)?;
-            }
-            '\n' | '\r' => {
-                // Skip existing newlines to avoid double spacing
+/// Reads the input from the given file, stdin or --expr, and returns it together with the path that is used in messages.
+fn read_input(cli: &Cli) -> Result<(PathBuf, String), Box<dyn Error>> {
+    let stdin_path = |default: &str| cli.stdin_filename.clone().unwrap_or_else(|| PathBuf::from(default));
+
+    if let Some(expr) = &cli.expr {
+        return Ok((stdin_path("<expr>"), expr.clone()));
+    }
+
+    match cli.input.as_deref().expect("clap requires an input or --expr without a subcommand") {
+        "-" => {
+            let mut input = String::new();
+            stdin().read_to_string(&mut input)?;
+            Ok((stdin_path("<stdin>"), input))
+        }
+        path => {
+            if cli.stdin_filename.is_some() {
+                return Err("--stdin-filename can only be used when the input is read from stdin or given by --expr".into());
             }
-            _ => {
-                write!(writer, "{}", ch)?;
+
+            let input_path = Path::new(path);
+            if !input_path.exists() {
+                return Err(format!("Cannot find file {}", input_path.display()).into());
             }
+
+            Ok((input_path.to_path_buf(), fs::read_to_string(input_path)?))
+        }
+    }
+}
+
+/// Reports the differences of all files in the given paths.
+fn run_report(args: &ReportArgs, config: &Config) -> Result<Vec<Outcome>, Box<dyn Error>> {
+    let registry = args.backends.registry(config)?;
+    let checker = registry.checker(&args.backends.from, &args.backends.to)?;
+
+    let files = if let Some(revision) = &args.changed_since {
+        select_files(git::changed_since(revision)?, &args.paths, config)
+    } else if args.staged {
+        select_files(git::staged()?, &args.paths, config)
+    } else {
+        collect_files(&args.paths, config)?
+    };
+
+    let mut reports = report::check_files(&checker, files, args.staged, args.mcf, args.type_check, config)?;
+
+    if args.matrix {
+        return run_report_matrix(&registry, &reports);
+    }
+
+    if let Some(path) = &args.write_baseline {
+        let baseline = Baseline::from_reports(path, &reports)?;
+        baseline.write(path)?;
+        baseline.apply(&mut reports);
+    }
+
+    if let Some(path) = &args.baseline {
+        for entry in Baseline::read(path)?.apply(&mut reports) {
+            eprintln!(
+                "Warning: the baseline entry {} for {} no longer applies",
+                entry.fingerprint, entry.path
+            );
         }
     }
-    writeln!(writer)?;
-    Ok(())
-}
\ No newline at end of file
+
+    let format = if args.markdown {
+        ReportFormat::Markdown
+    } else {
+        args.format.or(config.format).unwrap_or(ReportFormat::Text)
+    };
+
+    match format {
+        ReportFormat::Markdown => {
+            let mut output = String::new();
+            report::write_markdown(&mut output, &reports)?;
+            print!("{}", output);
+        }
+        ReportFormat::Text => report::write_text(&mut stdout(), &args.diff.options(config), &reports)?,
+    }
+
+    Ok(reports.iter().map(|report| report.outcome()).collect())
+}
+
+/// Prints the canonical AST of the file for git, or the file itself when it cannot be parsed so that `git diff` still works.
+fn run_textconv(checker: &Checker, args: &TextconvArgs, config: &Config) -> Result<Vec<Outcome>, Box<dyn Error>> {
+    let input = fs::read_to_string(&args.path)?;
+    let mcf = config.is_mcf(&args.path, &input, args.mcf);
+
+    let result = match args.release {
+        Release::Previous => checker.print_ast_previous(&input, InputKind::from_mcf(mcf)),
+        Release::Current => checker.print_ast_current(&input, InputKind::from_mcf(mcf)),
+    };
+
+    match result {
+        Ok(ast) => {
+            let layout = Layout {
+                width: args.width,
+                indent: args.indent_width,
+            };
+            print!("{}", layout.format(&ast));
+        }
+        Err(error) if error.is::<ParseError>() => {
+            eprintln!("Warning: {}: {}", args.path.display(), error);
+            print!("{}", input);
+        }
+        Err(error) => return Err(error),
+    }
+
+    Ok(Vec::new())
+}
+
+fn print(cli: &Cli, checker: &Checker, mcf: bool, input: &str) -> Result<String, Box<dyn Error>> {
+    // If the user wants to print the parse tree, print it depending on the specified options.
+    if cli.print {
+        checker.print_ast_current(input, InputKind::from_mcf(mcf))
+    } else {
+        checker.print_ast_previous(input, InputKind::from_mcf(mcf))
+    }
+}
@@ -0,0 +1,124 @@
+//! Compares how every pair of registered backends parses an input.
+
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+use crate::Comparison;
+use crate::InputKind;
+use crate::Outcome;
+use crate::ParseError;
+use crate::Registry;
+
+/// Parses the input with every backend, and prints a table with the outcome of every pair of backends.
+///
+/// Parse errors are printed to stderr, and the outcome of every pair is returned.
+pub fn run_matrix(
+    f: &mut impl Write,
+    registry: &Registry,
+    path: &Path,
+    input: &str,
+    kind: InputKind,
+) -> Result<Vec<Outcome>, Box<dyn Error>> {
+    let mut results = Vec::new();
+    for backend in registry.backends() {
+        match backend.parse(input, kind) {
+            Ok(ast) => results.push(Ok(ast)),
+            Err(error) if error.is::<ParseError>() => {
+                eprintln!("{}: {}", path.display(), error);
+                results.push(Err(Outcome::ParseError));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let names: Vec<&str> = registry.backends().iter().map(|backend| backend.name()).collect();
+    let mut rows = Vec::new();
+    let mut outcomes = Vec::new();
+    for (i, previous) in results.iter().enumerate() {
+        let mut row = vec![names[i].to_string()];
+        for (j, current) in results.iter().enumerate() {
+            if i == j {
+                row.push("-".to_string());
+                continue;
+            }
+
+            let outcome = match (previous, current) {
+                (Ok(previous), Ok(current)) => Comparison {
+                    current: current.clone(),
+                    previous: previous.clone(),
+                }
+                .outcome(input),
+                _ => Outcome::ParseError,
+            };
+
+            row.push(
+                match outcome {
+                    Outcome::Identical => "identical",
+                    Outcome::Difference => "different",
+                    Outcome::ParseError | Outcome::InternalError => "error",
+                }
+                .to_string(),
+            );
+
+            // Every unordered pair is only counted once.
+            if i < j {
+                outcomes.push(outcome);
+            }
+        }
+        rows.push(row);
+    }
+
+    let header: Vec<String> = std::iter::once(String::new())
+        .chain(names.iter().map(|name| name.to_string()))
+        .collect();
+    write_table(f, &header, &rows)?;
+    Ok(outcomes)
+}
+
+/// Writes the rows as a table, where every column is as wide as its widest cell.
+fn write_table(f: &mut impl Write, header: &[String], rows: &[Vec<String>]) -> std::io::Result<()> {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            std::iter::once(header)
+                .chain(rows.iter().map(Vec::as_slice))
+                .map(|row| row[column].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in std::iter::once(header).chain(rows.iter().map(Vec::as_slice)) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        writeln!(f, "{}", cells.join("  ").trim_end())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_table() {
+        let header = vec![String::new(), "a".to_string(), "bb".to_string()];
+        let rows = vec![
+            vec!["a".to_string(), "-".to_string(), "identical".to_string()],
+            vec!["bb".to_string(), "identical".to_string(), "-".to_string()],
+        ];
+
+        let mut output = Vec::new();
+        write_table(&mut output, &header, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "    a          bb\n\
+             a   -          identical\n\
+             bb  identical  -\n"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Reads the projects of the mCRL2 IDE (mcrl2ide).
+//!
+//! A project consists of a `.mcrl2proj` file, a specification and a
+//! `properties` directory that contains a `.mcf` file for every property. The
+//! project file is an XML document that may give the location of the
+//! specification, for example:
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! <root>
+//!     <spec>buffer.mcrl2</spec>
+//! </root>
+//! ```
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The extension of mCRL2 IDE project files.
+const PROJECT_EXTENSION: &str = "mcrl2proj";
+
+/// The name of the specification in projects of some versions of the mCRL2 IDE, which do not give its location.
+const DEFAULT_SPECIFICATION: &str = "mcrl2spec.mcrl2";
+
+/// The files of an mCRL2 IDE project.
+pub struct Project {
+    /// The specification of the project.
+    pub specification: PathBuf,
+
+    /// The properties of the project, sorted by their path.
+    pub properties: Vec<PathBuf>,
+}
+
+/// Returns true iff the file at the given path is an mCRL2 IDE project file.
+pub fn is_project(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == PROJECT_EXTENSION)
+}
+
+impl Project {
+    /// Reads the project file at the given path.
+    ///
+    /// The specification is given by the `<spec>` element, relative to the
+    /// directory of the project file, and otherwise it is the `.mcrl2` file
+    /// with the same name as the project, or `mcrl2spec.mcrl2` when that does
+    /// not exist.
+    pub fn read(path: &Path) -> Result<Project, Box<dyn Error>> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Cannot read project {}: {}", path.display(), e))?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        let specification = match element_text(&contents, "spec") {
+            Some(spec) => directory.join(spec),
+            None => {
+                let specification = path.with_extension("mcrl2");
+                if !specification.is_file() && directory.join(DEFAULT_SPECIFICATION).is_file() {
+                    directory.join(DEFAULT_SPECIFICATION)
+                } else {
+                    specification
+                }
+            }
+        };
+
+        if !specification.is_file() {
+            return Err(format!(
+                "Cannot find the specification {} of project {}",
+                specification.display(),
+                path.display()
+            )
+            .into());
+        }
+
+        let mut properties = Vec::new();
+        let properties_directory = directory.join("properties");
+        if properties_directory.is_dir() {
+            for entry in fs::read_dir(&properties_directory)? {
+                let property = entry?.path();
+                if property.extension().is_some_and(|ext| ext == "mcf") {
+                    properties.push(property);
+                }
+            }
+        }
+        properties.sort();
+
+        Ok(Project {
+            specification,
+            properties,
+        })
+    }
+}
+
+/// Returns the trimmed text of the first element with the given name, without interpreting XML entities.
+fn element_text<'a>(contents: &'a str, name: &str) -> Option<&'a str> {
+    let start = contents.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = start + contents[start..].find(&format!("</{name}>"))?;
+
+    Some(contents[start..end].trim()).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_element_text() {
+        let contents =
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<root>\n    <spec> specs/buffer.mcrl2 </spec>\n</root>\n";
+
+        assert_eq!(element_text(contents, "spec"), Some("specs/buffer.mcrl2"));
+        assert_eq!(element_text("<root>\n</root>\n", "spec"), None);
+    }
+
+    #[test]
+    fn test_read_project() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = |name: &str| directory.path().join(name);
+        fs::create_dir_all(path("specs")).unwrap();
+        fs::create_dir_all(path("properties")).unwrap();
+        for name in [
+            "specs/buffer.mcrl2",
+            "properties/nodeadlock.mcf",
+            "properties/deadlock.mcf",
+            "properties/notes.txt",
+        ] {
+            fs::write(path(name), "").unwrap();
+        }
+
+        // The specification is given by the project file.
+        fs::write(
+            path("buffer.mcrl2proj"),
+            "<root>\n    <spec>specs/buffer.mcrl2</spec>\n</root>\n",
+        )
+        .unwrap();
+        let project = Project::read(&path("buffer.mcrl2proj")).unwrap();
+        assert_eq!(project.specification, path("specs/buffer.mcrl2"));
+        assert_eq!(
+            project.properties,
+            vec![path("properties/deadlock.mcf"), path("properties/nodeadlock.mcf")]
+        );
+
+        // Otherwise the specification is named after the project, or mcrl2spec.mcrl2.
+        fs::write(path("queue.mcrl2proj"), "<root>\n</root>\n").unwrap();
+        let error = Project::read(&path("queue.mcrl2proj")).err().unwrap();
+        assert!(error.to_string().starts_with("Cannot find the specification"));
+
+        fs::write(path("mcrl2spec.mcrl2"), "").unwrap();
+        assert_eq!(
+            Project::read(&path("queue.mcrl2proj")).unwrap().specification,
+            path("mcrl2spec.mcrl2")
+        );
+
+        fs::write(path("queue.mcrl2"), "").unwrap();
+        assert_eq!(
+            Project::read(&path("queue.mcrl2proj")).unwrap().specification,
+            path("queue.mcrl2")
+        );
+    }
+}
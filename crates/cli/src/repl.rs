@@ -0,0 +1,263 @@
+//! An interactive shell that shows how both releases parse a line of input.
+//!
+//! Every line is parsed as a modal formula, an mCRL2 specification or a data
+//! expression, and both ASTs are printed next to each other where the part in
+//! which they differ is highlighted. Lines starting with `:` are commands.
+
+use std::error::Error;
+use std::ops::Range;
+
+use console::Style;
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use crate::CURRENT_LABEL;
+use crate::Checker;
+use crate::DiffOptions;
+use crate::InputKind;
+use crate::PREVIOUS_LABEL;
+
+/// Describes the commands of the shell.
+const HELP: &str = "Enter a line to parse it with both releases, or one of the following commands:
+  :mcf     Parses the lines as modal formulas
+  :mcrl2   Parses the lines as mCRL2 specifications
+  :data    Parses the lines as data expressions
+  :parens  Toggles between fully parenthesized output and omitting the parentheses around names
+  :help    Shows this message
+  :quit    Stops the shell, as does Ctrl-D";
+
+/// Returns the prompt of the shell for the kind of the lines.
+fn prompt(kind: InputKind) -> &'static str {
+    match kind {
+        InputKind::Mcf => "mcf> ",
+        InputKind::Mcrl2 => "mcrl2> ",
+        InputKind::DataExpression => "data> ",
+    }
+}
+
+/// Runs the shell until the input is closed or `:quit` is entered.
+pub fn run_repl(checker: &Checker, options: &DiffOptions) -> Result<(), Box<dyn Error>> {
+    let mut editor = DefaultEditor::new()?;
+    let mut kind = InputKind::Mcf;
+    let mut parenthesized = true;
+
+    println!("{}", HELP);
+    loop {
+        let line = match editor.readline(prompt(kind)) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error.into()),
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+
+        match line {
+            ":mcf" => kind = InputKind::Mcf,
+            ":mcrl2" => kind = InputKind::Mcrl2,
+            ":data" => kind = InputKind::DataExpression,
+            ":parens" => {
+                parenthesized = !parenthesized;
+                println!(
+                    "{}",
+                    if parenthesized {
+                        "Printing fully parenthesized ASTs."
+                    } else {
+                        "Omitting the parentheses around names."
+                    }
+                );
+            }
+            ":help" => println!("{}", HELP),
+            ":quit" => break,
+            _ if line.starts_with(':') => println!("Unknown command {}, see :help.", line),
+            _ => match parse(checker, line, kind) {
+                Ok((previous, current)) => {
+                    let (previous, current) = if parenthesized {
+                        (previous, current)
+                    } else {
+                        (omit_parentheses(&previous), omit_parentheses(&current))
+                    };
+
+                    print!("{}", render(options, &previous, &current));
+                }
+                Err(error) => println!("{}", Style::new().red().force_styling(options.color).apply_to(error)),
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the single line ASTs of the 202407.1 (previous) and 202507.0 (current) releases.
+fn parse(checker: &Checker, line: &str, kind: InputKind) -> Result<(String, String), Box<dyn Error>> {
+    let comparison = checker.compare(line, kind)?;
+    let single_line = |ast: &str| ast.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Ok((single_line(&comparison.previous), single_line(&comparison.current)))
+}
+
+/// Removes the parentheses that mCRL2 prints around every name and number, such as in `(a) && (b)`.
+fn omit_parentheses(ast: &str) -> String {
+    let is_name = |ch: char| ch.is_alphanumeric() || ch == '_' || ch == '\'';
+
+    let mut result = ast.to_string();
+    loop {
+        let chars: Vec<char> = result.chars().collect();
+        let mut output = String::new();
+        let mut index = 0;
+        let mut changed = false;
+
+        while index < chars.len() {
+            // The parentheses of an application such as `f(a)` are kept.
+            let applied = index > 0 && is_name(chars[index - 1]);
+            if chars[index] == '(' && !applied {
+                let end = chars[index + 1..]
+                    .iter()
+                    .position(|ch| !is_name(*ch))
+                    .map(|length| index + 1 + length);
+                if let Some(end) = end {
+                    if end > index + 1 && chars[end] == ')' {
+                        output.extend(&chars[index + 1..end]);
+                        index = end + 1;
+                        changed = true;
+                        continue;
+                    }
+                }
+            }
+
+            output.push(chars[index]);
+            index += 1;
+        }
+
+        result = output;
+        if !changed {
+            return result;
+        }
+    }
+}
+
+/// Returns the character ranges of both ASTs that differ, which start and end at the boundaries of names.
+fn differing_ranges(previous: &[char], current: &[char]) -> (Range<usize>, Range<usize>) {
+    let is_name = |ch: &char| ch.is_alphanumeric() || *ch == '_' || *ch == '\'';
+
+    let mut prefix = previous.iter().zip(current).take_while(|(left, right)| left == right).count();
+    let mut suffix = previous
+        .iter()
+        .rev()
+        .zip(current.iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count()
+        .min(previous.len().min(current.len()) - prefix);
+
+    // A name is split when the common part ends (or starts) in the middle of a name in one of the ASTs.
+    let splits =
+        |ast: &[char], index: usize| index > 0 && index < ast.len() && is_name(&ast[index - 1]) && is_name(&ast[index]);
+    while splits(previous, prefix) || splits(current, prefix) {
+        prefix -= 1;
+    }
+    while splits(previous, previous.len() - suffix) || splits(current, current.len() - suffix) {
+        suffix -= 1;
+    }
+
+    (prefix..previous.len() - suffix, prefix..current.len() - suffix)
+}
+
+/// Returns both ASTs in two columns, where the part in which they differ is highlighted.
+///
+/// Without colours the highlighted part is marked by `^` on the line below.
+fn render(options: &DiffOptions, previous: &str, current: &str) -> String {
+    if previous == current {
+        return format!("Both releases: {}\n", current);
+    }
+
+    let column = (options.width.saturating_sub(3) / 2).max(20);
+    let previous: Vec<char> = previous.chars().collect();
+    let current: Vec<char> = current.chars().collect();
+    let (previous_range, current_range) = differing_ranges(&previous, &current);
+
+    let bold = Style::new().bold().force_styling(options.color);
+    let mut output = format!(
+        "{}   {}\n",
+        bold.apply_to(format!("{PREVIOUS_LABEL:column$}")),
+        bold.apply_to(CURRENT_LABEL)
+    );
+
+    let rows = previous.len().max(current.len()).div_ceil(column);
+    for row in 0..rows {
+        let start = row * column;
+        let (left, left_marks) = render_row(options, &previous, &previous_range, start..start + column);
+        let (right, right_marks) = render_row(options, &current, &current_range, start..start + column);
+        output.push_str(format!("{}   {}", left, right).trim_end());
+        output.push('\n');
+
+        if !options.color && (left_marks.contains('^') || right_marks.contains('^')) {
+            output.push_str(format!("{}   {}", left_marks, right_marks).trim_end());
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Returns the characters of the AST in the given range padded to the width of the range, and the marks below them.
+fn render_row(options: &DiffOptions, ast: &[char], highlight: &Range<usize>, range: Range<usize>) -> (String, String) {
+    let style = Style::new().yellow().bold().force_styling(options.color);
+
+    let mut text = String::new();
+    let mut marks = String::new();
+    for index in range {
+        let ch = ast.get(index).copied().unwrap_or(' ');
+        if highlight.contains(&index) {
+            text.push_str(&style.apply_to(ch).to_string());
+            marks.push('^');
+        } else {
+            text.push(ch);
+            marks.push(' ');
+        }
+    }
+
+    (text, marks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_omit_parentheses() {
+        assert_eq!(omit_parentheses("form (a) && ((b) => (c));"), "form a && (b => c);");
+        assert_eq!(omit_parentheses("val(f((x)) + (1))"), "val(f(x) + 1)");
+    }
+
+    #[test]
+    fn test_render() {
+        let options = DiffOptions {
+            color: false,
+            width: 61,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            render(&options, "a && (b => c)", "(a && b) => c"),
+            "mCRL2 202407.1                  mCRL2 202507.0\n\
+             a && (b => c)                   (a && b) => c\n\
+             ^^^^^^^^^^^^^                   ^^^^^^^^^^^^^\n"
+        );
+        assert_eq!(
+            render(&options, "x || ab && (b => c)", "x || (ab && b) => c"),
+            "mCRL2 202407.1                  mCRL2 202507.0\n\
+             x || ab && (b => c)             x || (ab && b) => c\n\
+             \u{20}    ^^^^^^^^^^^^^^                  ^^^^^^^^^^^^^^\n"
+        );
+        assert_eq!(
+            render(&options, "x || a && (b => c)", "x || (a && b) => c"),
+            "mCRL2 202407.1                  mCRL2 202507.0\n\
+             x || a && (b => c)              x || (a && b) => c\n\
+             \u{20}    ^^^^^^^^^^^^^                   ^^^^^^^^^^^^^\n"
+        );
+    }
+}
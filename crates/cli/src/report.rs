@@ -0,0 +1,547 @@
+//! Reports the parsing differences for a collection of files.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::Checker;
+use crate::Comparison;
+use crate::CURRENT_LABEL;
+use crate::Config;
+use crate::DiffOptions;
+use crate::Difference;
+use crate::InputKind;
+use crate::fingerprint;
+use crate::git;
+use crate::insert_parentheses;
+use crate::Outcome;
+use crate::PREVIOUS_LABEL;
+use crate::Project;
+use crate::extract_snippets;
+use crate::is_document;
+use crate::is_project;
+use crate::is_script;
+use crate::kind_directive;
+use crate::print_labelled_diff;
+use crate::referenced_files;
+use crate::type_check_2025;
+use crate::statements::Statement;
+
+/// The result of checking a single file.
+pub struct FileReport {
+    /// The path of the checked file.
+    pub path: PathBuf,
+
+    /// Whether the file was checked as a modal formula.
+    pub mcf: bool,
+
+    /// The contents of the file, or the snippet that was checked when the file is a document.
+    pub input: String,
+
+    /// The (one-based) line in the file on which the input starts, which is only different from one for snippets.
+    pub line: usize,
+
+    /// The ASTs of both releases, or the error that occurred while parsing.
+    pub result: Result<Comparison, Box<dyn Error>>,
+
+    /// The fingerprints of the differences that have been accepted, see [fingerprint].
+    pub accepted: HashSet<String>,
+
+    /// The labels of the previous and current backend, such as `mCRL2 202407.1`, which are used in printed differences.
+    pub labels: (String, String),
+}
+
+impl FileReport {
+    /// Checks the given input of the file at the given path with the checker.
+    pub fn check(checker: &Checker, path: PathBuf, line: usize, input: String, mcf: bool) -> FileReport {
+        let result = checker.compare(&input, InputKind::from_mcf(mcf));
+
+        let report = FileReport {
+            path,
+            mcf,
+            input,
+            line,
+            result,
+            accepted: HashSet::new(),
+            labels: (
+                format!("mCRL2 {}", checker.previous().version()),
+                format!("mCRL2 {}", checker.current().version()),
+            ),
+        };
+
+        if let Ok(comparison) = &report.result {
+            for statement in comparison.unattached_directives(&report.input) {
+                eprintln!(
+                    "Warning: {}: the directive cannot be attached to a statement of the AST, so it has no effect",
+                    report.location(Some(&statement))
+                );
+            }
+        }
+
+        report
+    }
+
+    /// Returns the outcome of checking the file, where accepted differences are ignored.
+    pub fn outcome(&self) -> Outcome {
+        match &self.result {
+            Ok(_) if self.differences().is_empty() => Outcome::Identical,
+            result => Outcome::of(result),
+        }
+    }
+
+    /// Returns the differences of the file that have not been accepted or suppressed.
+    pub fn differences(&self) -> Vec<Difference<'_>> {
+        match &self.result {
+            Ok(comparison) => comparison
+                .differences(&self.input)
+                .into_iter()
+                .filter(|difference| !difference.is_suppressed() && !self.accepted.contains(&fingerprint(difference)))
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Returns true iff the file parses differently, or could not be parsed at all.
+    pub fn is_affected(&self) -> bool {
+        self.outcome() != Outcome::Identical
+    }
+
+    /// Returns the location of the given statement, or of the whole input, in the file.
+    pub fn location(&self, statement: Option<&Statement>) -> String {
+        match statement {
+            Some(statement) => format!("{}:{}", self.path.display(), self.line + statement.line(&self.input) - 1),
+            None if self.line > 1 => format!("{}:{}", self.path.display(), self.line),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    /// Returns a short description of the kind of input.
+    fn kind(&self) -> &'static str {
+        if self.mcf { "modal formula" } else { "specification" }
+    }
+
+    /// Returns the language used for fenced code blocks.
+    fn language(&self) -> &'static str {
+        if self.mcf { "mcf" } else { "mcrl2" }
+    }
+}
+
+/// Checks the given files, which are typically found by [crate::collect_files] or [crate::select_files].
+///
+/// Projects of the mCRL2 IDE are checked with [check_project], where `type_check` determines whether their properties are
+/// type checked. For shell scripts and Makefiles the files that they reference are checked, see [check_script].
+///
+/// When `staged` is set the files are read from the git index instead of the working tree, but the files that are
+/// referenced by projects and scripts are still read from the working tree.
+pub fn check_files(
+    checker: &Checker,
+    files: Vec<PathBuf>,
+    staged: bool,
+    mcf: bool,
+    type_check: bool,
+    config: &Config,
+) -> Result<Vec<FileReport>, Box<dyn Error>> {
+    let mut result = Vec::new();
+
+    for path in files {
+        if is_project(&path) {
+            result.extend(check_project(checker, &path, type_check)?);
+            continue;
+        }
+
+        let input = if staged {
+            git::staged_contents(&path)?
+        } else {
+            fs::read_to_string(&path)?
+        };
+
+        if is_script(&path) {
+            result.extend(check_script(checker, &path, &input, config)?);
+        } else if is_document(&path) {
+            result.extend(check_document(checker, &path, &input));
+        } else {
+            let mcf = config.is_mcf(&path, &input, mcf);
+            result.push(FileReport::check(checker, path, 1, input, mcf));
+        }
+    }
+
+    Ok(result)
+}
+
+/// Checks the specification and every property of the mCRL2 IDE project at the given path, see [Project].
+///
+/// When `type_check` is set the properties are also type checked against the specification, unless one of them cannot be parsed.
+pub fn check_project(checker: &Checker, path: &Path, type_check: bool) -> Result<Vec<FileReport>, Box<dyn Error>> {
+    let project = Project::read(path)?;
+
+    let input = fs::read_to_string(&project.specification)?;
+    let specification = FileReport::check(checker, project.specification, 1, input, false);
+
+    let mut result = Vec::new();
+    for property in project.properties {
+        let input = fs::read_to_string(&property)?;
+        let mut report = FileReport::check(checker, property, 1, input, true);
+
+        if type_check && specification.result.is_ok() && report.result.is_ok() {
+            if let Err(error) = type_check_2025(&specification.input, &report.input) {
+                report.result = Err(error);
+            }
+        }
+
+        result.push(report);
+    }
+
+    result.insert(0, specification);
+    Ok(result)
+}
+
+/// Checks the .mcrl2 and .mcf files that are referenced by the shell script or Makefile at the given path, see [referenced_files].
+///
+/// Referenced files that do not exist, for example because the script generates them, are skipped with a warning.
+pub fn check_script(
+    checker: &Checker,
+    path: &Path,
+    input: &str,
+    config: &Config,
+) -> Result<Vec<FileReport>, Box<dyn Error>> {
+    let mut result = Vec::new();
+
+    for file in referenced_files(path, input) {
+        if !file.is_file() {
+            eprintln!(
+                "Warning: {} is referenced by {}, but does not exist",
+                file.display(),
+                path.display()
+            );
+            continue;
+        }
+
+        let input = fs::read_to_string(&file)?;
+        let mcf = config.is_mcf(&file, &input, false);
+        result.push(FileReport::check(checker, file, 1, input, mcf));
+    }
+
+    Ok(result)
+}
+
+/// Checks every snippet in the given document, see [extract_snippets].
+///
+/// The kind of a snippet is determined by the language of its block, unless its first line contains a kind directive.
+pub fn check_document(checker: &Checker, path: &Path, input: &str) -> Vec<FileReport> {
+    extract_snippets(path, input)
+        .into_iter()
+        .map(|snippet| {
+            let mcf = kind_directive(&snippet.text).unwrap_or(snippet.mcf);
+            FileReport::check(checker, path.to_path_buf(), snippet.line, snippet.text, mcf)
+        })
+        .collect()
+}
+
+/// Writes a plain text report that lists the differences of every affected file.
+pub fn write_text(f: &mut impl io::Write, options: &DiffOptions, reports: &[FileReport]) -> io::Result<()> {
+    for report in reports {
+        write_file_text(f, options, report)?;
+    }
+
+    writeln!(f)?;
+    write_summary(f, reports)
+}
+
+/// Writes the outcome and differences of a single file as plain text.
+pub fn write_file_text(f: &mut impl io::Write, options: &DiffOptions, report: &FileReport) -> io::Result<()> {
+    match &report.result {
+        Err(error) => writeln!(f, "{}: {}: {}", report.location(None), describe(report.outcome()), error)?,
+        Ok(_) if report.outcome() == Outcome::Identical => writeln!(f, "{}: identical", report.location(None))?,
+        Ok(_) => {
+            for difference in report.differences() {
+                writeln!(
+                    f,
+                    "{}: parsed differently [{}]",
+                    report.location(difference.original.as_ref()),
+                    difference.rule().id()
+                )?;
+
+                print_labelled_diff(
+                    f,
+                    options,
+                    (&report.labels.0, &report.labels.1),
+                    difference.previous,
+                    difference.current,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the number of files per outcome.
+pub fn write_summary(f: &mut impl io::Write, reports: &[FileReport]) -> io::Result<()> {
+    let count = |outcome| reports.iter().filter(|report| report.outcome() == outcome).count();
+
+    writeln!(
+        f,
+        "Checked {} file(s): {} identical, {} with differences, {} with parse errors, {} with internal errors.",
+        reports.len(),
+        count(Outcome::Identical),
+        count(Outcome::Difference),
+        count(Outcome::ParseError),
+        count(Outcome::InternalError)
+    )
+}
+
+/// Returns a short description of the outcome.
+fn describe(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Identical => "identical",
+        Outcome::Difference => "parsed differently",
+        Outcome::ParseError => "parse error",
+        Outcome::InternalError => "internal error",
+    }
+}
+
+/// Writes a migration report in Markdown, which lists every difference with a rewrite that preserves the previous meaning.
+pub fn write_markdown(f: &mut impl fmt::Write, reports: &[FileReport]) -> fmt::Result {
+    let affected: Vec<_> = reports.iter().filter(|report| report.is_affected()).collect();
+
+    // All reports are checked by the same checker.
+    let (previous, current) = reports.first().map_or((PREVIOUS_LABEL, CURRENT_LABEL), |report| {
+        (report.labels.0.as_str(), report.labels.1.as_str())
+    });
+    writeln!(f, "# Parsing differences between {} and {}", previous, current)?;
+    writeln!(f)?;
+    writeln!(
+        f,
+        "Checked {} file(s), of which {} are affected by the changed operator precedences.",
+        reports.len(),
+        affected.len()
+    )?;
+
+    if affected.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f)?;
+    writeln!(f, "| File | Kind | Differences |")?;
+    writeln!(f, "| ---- | ---- | ----------- |")?;
+    for report in &affected {
+        let status = match &report.result {
+            Ok(_) => report.differences().len().to_string(),
+            Err(_) => describe(report.outcome()).to_string(),
+        };
+
+        writeln!(f, "| {} | {} | {} |", markdown_location(report), report.kind(), status)?;
+    }
+
+    for report in &affected {
+        writeln!(f)?;
+        writeln!(f, "## {}", markdown_location(report))?;
+
+        if let Err(error) = &report.result {
+            writeln!(f)?;
+            writeln!(f, "The file could not be checked:")?;
+            writeln!(f)?;
+            write_code_block(f, "text", &error.to_string())?;
+            continue;
+        }
+
+        for (index, difference) in report.differences().iter().enumerate() {
+            writeln!(f)?;
+            match &difference.original {
+                Some(original) => writeln!(
+                    f,
+                    "### Difference {} (line {}, rule `{}`)",
+                    index + 1,
+                    report.line + original.line(&report.input) - 1,
+                    difference.rule().id()
+                )?,
+                None => writeln!(f, "### Difference {} (rule `{}`)", index + 1, difference.rule().id())?,
+            }
+
+            writeln!(f)?;
+            writeln!(f, "Original text:")?;
+            writeln!(f)?;
+            write_code_block(
+                f,
+                report.language(),
+                difference.original.as_ref().map_or(report.input.trim(), |s| s.text),
+            )?;
+
+            writeln!(f)?;
+            writeln!(f, "Grouping in {}:", previous)?;
+            writeln!(f)?;
+            write_code_block(f, report.language(), difference.previous)?;
+
+            writeln!(f)?;
+            writeln!(f, "Grouping in {}:", current)?;
+            writeln!(f)?;
+            write_code_block(f, report.language(), difference.current)?;
+
+            writeln!(f)?;
+            match suggested_rewrite(difference) {
+                Some(rewrite) => {
+                    writeln!(f, "Suggested rewrite that keeps the {} meaning:", previous)?;
+                    writeln!(f)?;
+                    write_code_block(f, report.language(), &rewrite)?;
+                }
+                None => {
+                    // The printed AST is fully parenthesized, so it parses the same in both releases.
+                    writeln!(
+                        f,
+                        "The original text cannot be rewritten, but the grouping in {} keeps its meaning:",
+                        previous
+                    )?;
+                    writeln!(f)?;
+                    write_code_block(f, report.language(), &format!("{};", previous_grouping(difference)))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the original text of the difference with the parentheses that give it the grouping of the previous release.
+///
+/// Returns None when there is no original statement, or when its own parentheses conflict with that grouping.
+fn suggested_rewrite(difference: &Difference) -> Option<String> {
+    let original = difference.original.as_ref()?;
+
+    let insertions = insert_parentheses(original.text, previous_grouping(difference))?;
+
+    let mut rewrite = original.text.to_string();
+    for (offset, parentheses) in insertions.iter().rev() {
+        rewrite.insert_str(*offset, parentheses);
+    }
+
+    Some(rewrite)
+}
+
+/// Returns the difference as parsed by the previous release, without a terminating semicolon.
+///
+/// This is the whole AST when the statements cannot be matched, which does end in a semicolon.
+fn previous_grouping<'a>(difference: &Difference<'a>) -> &'a str {
+    difference.previous.trim_end().trim_end_matches(';')
+}
+
+/// Writes the text as a fenced code block.
+fn write_code_block(f: &mut impl fmt::Write, language: &str, text: &str) -> fmt::Result {
+    // Use a longer fence when the text itself contains one.
+    let fence = if text.contains("```") { "~~~~" } else { "```" };
+
+    writeln!(f, "{fence}{language}")?;
+    writeln!(f, "{}", text.trim_end())?;
+    writeln!(f, "{fence}")
+}
+
+/// Formats the path of the report as inline code that can be used in tables and headings, followed by the line of a snippet.
+fn markdown_location(report: &FileReport) -> String {
+    let path = format!("`{}`", report.path.display().to_string().replace('|', "\\|"));
+
+    if report.line > 1 {
+        format!("{} (line {})", path, report.line)
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseError;
+
+    /// Returns the report of a file whose input is parsed into the given ASTs.
+    fn report(path: &str, mcf: bool, input: &str, previous: &str, current: &str) -> FileReport {
+        FileReport {
+            path: PathBuf::from(path),
+            mcf,
+            input: input.to_string(),
+            line: 1,
+            result: Ok(Comparison {
+                current: current.to_string(),
+                previous: previous.to_string(),
+            }),
+            accepted: HashSet::new(),
+            labels: ("mCRL2 202407.1".to_string(), "mCRL2 202507.0".to_string()),
+        }
+    }
+
+    fn reports() -> Vec<FileReport> {
+        let mut error = report("broken.mcf", true, "[a]", "", "");
+        error.result = Err(ParseError {
+            release: "202507.0".to_string(),
+            message: "syntax error".to_string(),
+        }
+        .into());
+
+        vec![
+            report("same.mcf", true, "true", "form true;", "form true;"),
+            report(
+                "spec.mcrl2",
+                false,
+                "act a, b, c;\nproc P = a . % first\n  b + c;\ninit P;\n",
+                "act a, b, c;\nproc P = (a) . ((b) + (c));\ninit P;\n",
+                "act a, b, c;\nproc P = ((a) . (b)) + (c);\ninit P;\n",
+            ),
+            // The ASTs have a different number of statements, so the statements cannot be matched.
+            report(
+                "property.mcf",
+                true,
+                "nu X. [a]X && <b>true",
+                "form (nu X. ([a]X)) && (<b>true);\nform true;\n",
+                "form nu X. (([a]X) && (<b>true));\n",
+            ),
+            error,
+        ]
+    }
+
+    #[test]
+    fn test_write_markdown() {
+        let mut output = String::new();
+        write_markdown(&mut output, &reports()).unwrap();
+
+        assert!(output.starts_with(
+            "# Parsing differences between mCRL2 202407.1 and mCRL2 202507.0\n\n\
+             Checked 4 file(s), of which 3 are affected by the changed operator precedences.\n\n\
+             | File | Kind | Differences |\n\
+             | ---- | ---- | ----------- |\n\
+             | `spec.mcrl2` | specification | 1 |\n\
+             | `property.mcf` | modal formula | 1 |\n\
+             | `broken.mcf` | modal formula | parse error |\n"
+        ));
+        assert!(!output.contains("same.mcf"));
+
+        // The rewrite keeps the comments and layout of the original statement.
+        assert!(output.contains(
+            "Suggested rewrite that keeps the mCRL2 202407.1 meaning:\n\n\
+             ```mcrl2\nproc P = a . % first\n  (b + c)\n```\n"
+        ));
+
+        // The whole AST is suggested when the original text cannot be rewritten.
+        assert!(output.contains(
+            "The original text cannot be rewritten, but the grouping in mCRL2 202407.1 keeps its meaning:\n\n\
+             ```mcf\nform (nu X. ([a]X)) && (<b>true);\nform true;\n```\n"
+        ));
+        assert!(!output.contains(";;"));
+
+        assert!(output.contains("## `broken.mcf`\n\nThe file could not be checked:\n"));
+    }
+
+    #[test]
+    fn test_write_text() {
+        let mut output = Vec::new();
+        write_text(&mut output, &DiffOptions::default(), &reports()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("same.mcf: identical\nspec.mcrl2:2: parsed differently ["));
+        assert!(output.contains("property.mcf:1: parsed differently ["));
+        assert!(output.contains("broken.mcf: parse error: mCRL2 202507.0 cannot parse the input: syntax error\n"));
+        assert!(output.ends_with(
+            "\nChecked 4 file(s): 1 identical, 2 with differences, 1 with parse errors, 0 with internal errors.\n"
+        ));
+    }
+}
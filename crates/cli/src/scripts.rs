@@ -0,0 +1,101 @@
+//! Finds the specifications and modal formulas that are used by verification pipelines.
+//!
+//! Pipelines are shell scripts and Makefiles that call the tools of the
+//! toolset, for example `mcrl22lps buffer.mcrl2 buffer.lps` and `lps2pbes -f
+//! deadlock.mcf buffer.lps deadlock.pbes`. Every argument that ends with
+//! `.mcrl2` or `.mcf` is a referenced file, where arguments that contain
+//! variables or wildcards are skipped since their value is not known.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The extensions of shell scripts and Makefiles.
+const SCRIPT_EXTENSIONS: [&str; 4] = ["sh", "bash", "zsh", "mk"];
+
+/// The file names of Makefiles.
+const MAKEFILE_NAMES: [&str; 3] = ["Makefile", "makefile", "GNUmakefile"];
+
+/// The characters that separate the arguments of a command, besides whitespace.
+const SEPARATORS: &str = ";|&<>()`";
+
+/// The characters of arguments whose value is only known when the script runs.
+const UNKNOWN: &str = "$*?[%{";
+
+/// Returns true iff the file at the given path is a shell script or Makefile.
+pub fn is_script(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| SCRIPT_EXTENSIONS.iter().any(|script| ext == *script))
+        || path
+            .file_name()
+            .is_some_and(|name| MAKEFILE_NAMES.iter().any(|makefile| name == *makefile))
+}
+
+/// Returns the .mcrl2 and .mcf files that are referenced by the given script, relative to the directory of the script.
+///
+/// Every file is returned once, in the order in which it is first referenced.
+pub fn referenced_files(path: &Path, input: &str) -> Vec<PathBuf> {
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut result: Vec<PathBuf> = Vec::new();
+
+    // Lines that end with a backslash are continued on the next line.
+    for line in input.replace("\\\r\n", " ").replace("\\\n", " ").lines() {
+        for argument in arguments(line) {
+            if argument.contains(|ch| UNKNOWN.contains(ch)) {
+                continue;
+            }
+
+            let file = directory.join(argument);
+            if file.extension().is_some_and(|ext| ext == "mcrl2" || ext == "mcf") && !result.contains(&file) {
+                result.push(file);
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the arguments on the given line without quotes, where the value of options such as `--formula=file.mcf` is also an argument.
+fn arguments(line: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for ch in line.chars() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => current.push(ch),
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '#' && current.is_empty() => break,
+            None if ch.is_whitespace() || SEPARATORS.contains(ch) => result.push(std::mem::take(&mut current)),
+            None => current.push(ch),
+        }
+    }
+    result.push(current);
+
+    result
+        .into_iter()
+        .filter(|argument| !argument.is_empty())
+        .map(|argument| match argument.split_once('=') {
+            Some((_, value)) => value.to_string(),
+            None => argument,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_referenced_files() {
+        let input = "#!/bin/sh\n# lps2pbes -f old.mcf\nmcrl22lps -v \"specs/buffer.mcrl2\" buffer.lps\nlps2pbes --formula=properties/deadlock.mcf \\\n  buffer.lps deadlock.pbes && pbes2bool deadlock.pbes\nfor f in properties/*.mcf; do lps2pbes -f $f buffer.lps; done\nmcrl22lps specs/buffer.mcrl2 | lpsinfo\n";
+
+        assert_eq!(
+            referenced_files(Path::new("pipeline/verify.sh"), input),
+            vec![
+                PathBuf::from("pipeline/specs/buffer.mcrl2"),
+                PathBuf::from("pipeline/properties/deadlock.mcf")
+            ]
+        );
+    }
+}
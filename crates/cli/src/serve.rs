@@ -0,0 +1,245 @@
+//! A local HTTP server that compares the ASTs of both releases for other tools.
+//!
+//! The server accepts `POST /compare` requests with a JSON body, for example
+//! `{"text": "[true*]<true>true", "kind": "mcf"}`, where the kind is `mcf` or
+//! `mcrl2` and is determined from the text when it is omitted. The response
+//! contains the outcome, the ASTs of both releases and the parse errors:
+//!
+//! ```json
+//! {"status": "difference", "previous": "...", "current": "...", "errors": []}
+//! ```
+
+use std::error::Error;
+use std::io::Read;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tiny_http::Header;
+use tiny_http::Method;
+use tiny_http::Request;
+use tiny_http::Response;
+use tiny_http::Server;
+
+use crate::Checker;
+use crate::InputKind;
+use crate::Outcome;
+use crate::ParseError;
+use crate::sniff_mcf;
+
+/// The maximum size of a request body in bytes.
+const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// The kind of the text in a request.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum RequestKind {
+    Mcf,
+    Mcrl2,
+}
+
+/// The body of a comparison request.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompareRequest {
+    text: String,
+    kind: Option<RequestKind>,
+}
+
+/// The body of a comparison response.
+#[derive(Serialize)]
+struct CompareResponse {
+    /// The outcome of the comparison.
+    status: Outcome,
+
+    /// The AST printed by the 202407.1 release, unless it cannot parse the text.
+    previous: Option<String>,
+
+    /// The AST printed by the 202507.0 release, unless it cannot parse the text.
+    current: Option<String>,
+
+    /// The errors of both releases.
+    errors: Vec<ErrorResponse>,
+}
+
+/// An error that occurred while parsing the text.
+#[derive(Serialize)]
+struct ErrorResponse {
+    /// The release that cannot parse the text, which is absent for internal errors.
+    release: Option<String>,
+
+    /// The error message.
+    message: String,
+}
+
+/// Answers requests on the given port of localhost until the process is stopped.
+///
+/// All calls into mcrl2-sys are serialised by its global lock, so the requests
+/// are answered one at a time and other connections wait in the queue of the server.
+pub fn serve(checker: &Checker, port: u16) -> Result<(), Box<dyn Error>> {
+    let server =
+        Server::http(("127.0.0.1", port)).map_err(|error| format!("Cannot listen on port {}: {}", port, error))?;
+    println!("Listening on http://127.0.0.1:{}, press Ctrl-C to stop.", port);
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = handle(checker, &mut request);
+
+        let header = Header::from_bytes("Content-Type", "application/json").expect("the header is valid");
+        let response = Response::from_string(body).with_status_code(status).with_header(header);
+        if let Err(error) = request.respond(response) {
+            eprintln!("Cannot send the response: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the status code and JSON body of the response to the request.
+fn handle(checker: &Checker, request: &mut Request) -> (u16, String) {
+    if request.url() != "/compare" {
+        return error_response(404, "Unknown endpoint, use POST /compare");
+    }
+
+    if *request.method() != Method::Post {
+        return error_response(405, "Only POST requests are supported");
+    }
+
+    let mut body = String::new();
+    if let Err(error) = request.as_reader().take(MAX_BODY_SIZE + 1).read_to_string(&mut body) {
+        return error_response(400, &format!("Cannot read the request: {}", error));
+    }
+
+    if body.len() as u64 > MAX_BODY_SIZE {
+        return error_response(413, "The request is too large");
+    }
+
+    match serde_json::from_str::<CompareRequest>(&body) {
+        Ok(request) => {
+            let response = compare_request(checker, &request);
+            (
+                200,
+                serde_json::to_string(&response).expect("the response is valid JSON"),
+            )
+        }
+        Err(error) => error_response(400, &format!("Invalid request: {}", error)),
+    }
+}
+
+/// Parses the text of the request with both releases.
+fn compare_request(checker: &Checker, request: &CompareRequest) -> CompareResponse {
+    let kind = match request.kind {
+        Some(RequestKind::Mcf) => InputKind::Mcf,
+        Some(RequestKind::Mcrl2) => InputKind::Mcrl2,
+        None => InputKind::from_mcf(sniff_mcf(&request.text)),
+    };
+
+    let outcome = checker.check(&request.text, kind);
+
+    let mut errors = Vec::new();
+    let mut ast = |result: Result<String, Box<dyn Error>>| match result {
+        Ok(ast) => Some(ast),
+        Err(error) => {
+            errors.push(match error.downcast::<ParseError>() {
+                Ok(error) => ErrorResponse {
+                    release: Some(error.release.clone()),
+                    message: error.message,
+                },
+                Err(error) => ErrorResponse {
+                    release: None,
+                    message: error.to_string(),
+                },
+            });
+            None
+        }
+    };
+
+    CompareResponse {
+        status: outcome.outcome,
+        previous: ast(outcome.previous),
+        current: ast(outcome.current),
+        errors,
+    }
+}
+
+/// Returns the status code and JSON body of an error response.
+fn error_response(status: u16, message: &str) -> (u16, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::InProcessBackend;
+
+    /// Returns a checker whose previous release parses `&&` as `||`, and cannot parse inputs that contain `error`.
+    fn checker() -> Checker {
+        Checker::between(
+            Arc::new(InProcessBackend::new("previous", |input, _mcf| {
+                if input.contains("error") {
+                    Err(ParseError {
+                        release: "previous".to_string(),
+                        message: "syntax error".to_string(),
+                    }
+                    .into())
+                } else {
+                    Ok(format!("form {};", input.replace("&&", "||")))
+                }
+            })),
+            Arc::new(InProcessBackend::new("current", |input, _mcf| {
+                Ok(format!("form {};", input))
+            })),
+        )
+    }
+
+    /// Returns the JSON body of the response to the given request body.
+    fn respond(body: &str) -> serde_json::Value {
+        let request: CompareRequest = serde_json::from_str(body).unwrap();
+        serde_json::to_value(compare_request(&checker(), &request)).unwrap()
+    }
+
+    #[test]
+    fn test_compare_response() {
+        assert_eq!(
+            respond(r#"{"text": "[true*]<true>true", "kind": "mcf"}"#),
+            json!({
+                "status": "identical",
+                "previous": "form [true*]<true>true;",
+                "current": "form [true*]<true>true;",
+                "errors": [],
+            })
+        );
+
+        // The kind is determined from the text when it is omitted.
+        assert_eq!(
+            respond(r#"{"text": "nu X. [a]X && <b>true"}"#),
+            json!({
+                "status": "difference",
+                "previous": "form nu X. [a]X || <b>true;",
+                "current": "form nu X. [a]X && <b>true;",
+                "errors": [],
+            })
+        );
+
+        assert_eq!(
+            respond(r#"{"text": "[true*]error", "kind": "mcf"}"#),
+            json!({
+                "status": "parse-error",
+                "previous": null,
+                "current": "form [true*]error;",
+                "errors": [{"release": "previous", "message": "syntax error"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_compare_request() {
+        let request: CompareRequest = serde_json::from_str(r#"{"text": "form true;", "kind": "mcf"}"#).unwrap();
+        assert_eq!(request.text, "form true;");
+        assert!(matches!(request.kind, Some(RequestKind::Mcf)));
+
+        assert!(serde_json::from_str::<CompareRequest>(r#"{"text": "true", "kind": "lps"}"#).is_err());
+    }
+}
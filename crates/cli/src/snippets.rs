@@ -0,0 +1,283 @@
+//! Extracts the mCRL2 specifications and modal formulas that are embedded in documents.
+//!
+//! The snippets are the fenced `mcrl2` and `mcf` code blocks in Markdown, the
+//! `code-block` directives in reStructuredText and the `lstlisting`
+//! environments with `language=mcrl2` or `language=mcf` in LaTeX.
+
+use std::path::Path;
+
+/// The extensions of the documents from which snippets are extracted.
+const DOCUMENT_EXTENSIONS: [&str; 3] = ["md", "rst", "tex"];
+
+/// A specification or modal formula that is embedded in a document.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Snippet {
+    /// The text of the snippet, without the indentation of the surrounding block.
+    pub text: String,
+
+    /// The (one-based) line number in the document on which the text starts.
+    pub line: usize,
+
+    /// Whether the snippet is a modal formula, based on the language of the block.
+    pub mcf: bool,
+}
+
+/// Returns true iff the file at the given path is a document from which snippets are extracted.
+pub fn is_document(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| DOCUMENT_EXTENSIONS.iter().any(|document| ext == *document))
+}
+
+/// Extracts the snippets from the given document, where the format is determined by the extension of its path.
+pub fn extract_snippets(path: &Path, input: &str) -> Vec<Snippet> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("md") => extract_markdown(input),
+        Some("rst") => extract_restructured_text(input),
+        Some("tex") => extract_latex(input),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns whether the language of a code block is a modal formula, or None when it is not an mCRL2 language.
+fn language_is_mcf(language: &str) -> Option<bool> {
+    match language.to_ascii_lowercase().as_str() {
+        "mcf" => Some(true),
+        "mcrl2" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the number of leading spaces of the line.
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Removes at most the given number of leading spaces from the line.
+fn dedent(line: &str, indent: usize) -> &str {
+    &line[indentation(line).min(indent)..]
+}
+
+/// Extracts the fenced code blocks, i.e., those delimited by ``` or ~~~, with the `mcrl2` or `mcf` language.
+fn extract_markdown(input: &str) -> Vec<Snippet> {
+    let mut result = Vec::new();
+
+    // The fence character, fence length, indentation and snippet of the block that is currently open.
+    let mut open: Option<(char, usize, usize, Option<Snippet>)> = None;
+
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim_start();
+
+        match &mut open {
+            Some((marker, length, indent, snippet)) => {
+                // The closing fence consists of at least as many fence characters as the opening fence.
+                let rest = trimmed.trim_start_matches(*marker);
+                if trimmed.len() - rest.len() >= *length && rest.trim().is_empty() {
+                    result.extend(snippet.take());
+                    open = None;
+                } else if let Some(snippet) = snippet {
+                    snippet.text.push_str(dedent(line, *indent));
+                    snippet.text.push('\n');
+                }
+            }
+            None => {
+                let indent = indentation(line);
+                let Some(marker) = trimmed.chars().next().filter(|ch| *ch == '`' || *ch == '~') else {
+                    continue;
+                };
+
+                let length = trimmed.len() - trimmed.trim_start_matches(marker).len();
+                if indent > 3 || length < 3 {
+                    continue;
+                }
+
+                // The language is the first word of the info string, which may also be written as `{.mcf}`.
+                let info = &trimmed[length..];
+                let language = info
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("")
+                    .trim_matches(|ch| ch == '{' || ch == '}' || ch == '.');
+
+                let snippet = language_is_mcf(language).map(|mcf| Snippet {
+                    text: String::new(),
+                    line: index + 2,
+                    mcf,
+                });
+                open = Some((marker, length, indent, snippet));
+            }
+        }
+    }
+
+    // A block that is not closed extends to the end of the document.
+    if let Some((_, _, _, snippet)) = open {
+        result.extend(snippet);
+    }
+
+    result
+}
+
+/// Extracts the `code-block`, `code` and `sourcecode` directives with the `mcrl2` or `mcf` language.
+fn extract_restructured_text(input: &str) -> Vec<Snippet> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut result = Vec::new();
+
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let indent = indentation(line);
+        index += 1;
+
+        let Some(language) = ["code-block::", "code::", "sourcecode::"].iter().find_map(|directive| {
+            line.trim_start()
+                .strip_prefix("..")
+                .map(str::trim_start)
+                .and_then(|rest| rest.strip_prefix(directive))
+        }) else {
+            continue;
+        };
+        let Some(mcf) = language_is_mcf(language.trim()) else {
+            continue;
+        };
+
+        // Skip the options of the directive, such as `:linenos:`.
+        while index < lines.len() && indentation(lines[index]) > indent && lines[index].trim_start().starts_with(':') {
+            index += 1;
+        }
+
+        // The content consists of the lines that are indented more than the directive.
+        let start = index;
+        while index < lines.len() && (lines[index].trim().is_empty() || indentation(lines[index]) > indent) {
+            index += 1;
+        }
+
+        let Some(first) = (start..index).find(|line| !lines[*line].trim().is_empty()) else {
+            continue;
+        };
+
+        let content = &lines[first..index];
+        let content_indent = content
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| indentation(line))
+            .min()
+            .unwrap_or(0);
+
+        let text: String = content
+            .iter()
+            .map(|line| format!("{}\n", dedent(line, content_indent)))
+            .collect();
+
+        result.push(Snippet {
+            text: format!("{}\n", text.trim_end()),
+            line: first + 1,
+            mcf,
+        });
+    }
+
+    result
+}
+
+/// Extracts the `lstlisting` environments whose `language` option is `mcrl2` or `mcf`.
+fn extract_latex(input: &str) -> Vec<Snippet> {
+    const BEGIN: &str = "\\begin{lstlisting}";
+    const END: &str = "\\end{lstlisting}";
+
+    let mut result = Vec::new();
+    let mut open: Option<Option<Snippet>> = None;
+
+    for (index, line) in input.lines().enumerate() {
+        match &mut open {
+            Some(snippet) => {
+                if let Some(position) = line.find(END) {
+                    if let Some(mut snippet) = snippet.take() {
+                        snippet.text.push_str(&line[..position]);
+                        result.push(snippet);
+                    }
+                    open = None;
+                } else if let Some(snippet) = snippet {
+                    snippet.text.push_str(line);
+                    snippet.text.push('\n');
+                }
+            }
+            None => {
+                let Some(position) = line.find(BEGIN) else {
+                    continue;
+                };
+
+                // The options are given as `[key=value, ...]` directly after the environment.
+                let options = line[position + BEGIN.len()..]
+                    .trim_start()
+                    .strip_prefix('[')
+                    .and_then(|options| options.split_once(']'))
+                    .map_or("", |(options, _)| options);
+
+                let language = options.split(',').find_map(|option| {
+                    let (key, value) = option.split_once('=')?;
+                    (key.trim() == "language").then(|| value.trim().trim_matches(|ch| ch == '{' || ch == '}'))
+                });
+
+                open = Some(language.and_then(language_is_mcf).map(|mcf| Snippet {
+                    text: String::new(),
+                    line: index + 2,
+                    mcf,
+                }));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_markdown() {
+        let input = "# Properties\n\n```mcf\n[true*]<true>true\n```\n\n```python\nprint(1)\n```\n\n  ~~~~ {.mcrl2}\n  init a;\n  ~~~~\n";
+
+        assert_eq!(
+            extract_snippets(Path::new("README.md"), input),
+            vec![
+                Snippet {
+                    text: "[true*]<true>true\n".to_string(),
+                    line: 4,
+                    mcf: true
+                },
+                Snippet {
+                    text: "init a;\n".to_string(),
+                    line: 12,
+                    mcf: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_restructured_text() {
+        let input = "Deadlock freedom\n\n.. code-block:: mcf\n   :linenos:\n\n   [true*]\n     <true>true\n\nText\n";
+
+        assert_eq!(
+            extract_snippets(Path::new("index.rst"), input),
+            vec![Snippet {
+                text: "[true*]\n  <true>true\n".to_string(),
+                line: 6,
+                mcf: true
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extract_latex() {
+        let input = "\\begin{lstlisting}[caption=Buffer, language=mCRL2]\nact a;\ninit a;\n\\end{lstlisting}\n\\begin{lstlisting}\nignored\n\\end{lstlisting}\n";
+
+        assert_eq!(
+            extract_snippets(Path::new("notes.tex"), input),
+            vec![Snippet {
+                text: "act a;\ninit a;\n".to_string(),
+                line: 2,
+                mcf: false
+            }]
+        );
+    }
+}
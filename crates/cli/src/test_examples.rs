@@ -2,7 +2,8 @@
 mod tests {
     use test_case::test_case;
 
-    use crate::print_ast_2024;
+    use crate::Checker;
+    use crate::InputKind;
 
     #[test_case(include_str!("../../../examples/incorrect/Always eventually request_alt.mcf"), include_str!("../snapshot/Always eventually request_alt.mcf") ; "Always eventually request_alt.mcf")]
     #[test_case(include_str!("../../../examples/incorrect/Always eventually request.mcf"), include_str!("../snapshot/Always eventually request.mcf") ; "Always eventually request.mcf")]
@@ -27,7 +28,9 @@ mod tests {
         let expected_normalized = expected.replace("\r\n", "\n").replace("\r", "\n");
         assert_eq!(current_ast.trim(), expected_normalized.trim(), "The pretty printed AST does not match the expected output.");
 
-        let previous_ast = print_ast_2024(input, true).expect("Failed to print AST for the 2024 version.");
+        let previous_ast = Checker::new()
+            .print_ast_previous(input, InputKind::Mcf)
+            .expect("Failed to print AST for the 2024 version.");
         let previous_ast_normalized = previous_ast.replace("\r\n", "\n").replace("\r", "\n");
         assert_ne!(current_ast.trim(), previous_ast_normalized.trim(), "For the incorrect example, the ASTs should differ between the two versions.");
     }
@@ -0,0 +1,200 @@
+//! Watches the file system and checks the files again whenever they change.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::io::stdout;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::EventKind;
+use notify::RecursiveMode;
+use notify::Watcher;
+
+use crate::Checker;
+use crate::Config;
+use crate::DiffOptions;
+use crate::collect_files;
+use crate::report;
+
+/// The time to wait for more events after a change, since editors often write a file in several steps.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Checks all files in the given paths, and then checks the changed files until the process is stopped.
+///
+/// Only results that differ from the previous result of the same file are printed, and all
+/// checks use the checker, which should reuse one helper process, see [Checker::with_persistent_helper].
+pub fn watch(
+    checker: &Checker,
+    paths: &[String],
+    mcf: bool,
+    config: &Config,
+    options: &DiffOptions,
+) -> Result<(), Box<dyn Error>> {
+    // The events contain absolute paths, which are printed relative to the current directory.
+    let current_dir = env::current_dir()?;
+    let paths = paths
+        .iter()
+        .map(|path| Ok(fs::canonicalize(path)?.to_string_lossy().into_owned()))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    for path in &paths {
+        watcher.watch(Path::new(path), RecursiveMode::Recursive)?;
+    }
+
+    // Files that are named explicitly are always checked, like the report subcommand does.
+    let explicit: HashSet<PathBuf> = paths.iter().map(PathBuf::from).filter(|path| path.is_file()).collect();
+
+    let mut results = Results::default();
+    let files = collect_files(&paths, config)?;
+    let mut reports = Vec::new();
+    for file in files {
+        let file = file.strip_prefix(&current_dir).map_or(file.clone(), Path::to_path_buf);
+        let file_reports = report::check_files(checker, vec![file.clone()], false, mcf, false, config)?;
+
+        let output = render(options, &file_reports)?;
+        if file_reports.iter().any(|report| report.is_affected()) {
+            print!("{}", output);
+        }
+
+        results.update(file, output);
+        reports.extend(file_reports);
+    }
+
+    println!();
+    report::write_summary(&mut stdout(), &reports)?;
+    println!("Watching for changes, press Ctrl-C to stop.");
+
+    while let Ok(event) = receiver.recv() {
+        let mut changed = BTreeSet::new();
+        add_paths(&mut changed, event?);
+        while let Ok(event) = receiver.recv_timeout(SETTLE_TIME) {
+            add_paths(&mut changed, event?);
+        }
+
+        for file in files_to_check(changed, &current_dir, &explicit, config, &mut results) {
+            let output = render(
+                options,
+                &report::check_files(checker, vec![file.clone()], false, mcf, false, config)?,
+            )?;
+            if results.update(file, output.clone()) {
+                print!("{}", output);
+                stdout().flush()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The last output of every checked file, which is used to only print results that changed.
+#[derive(Default)]
+struct Results {
+    outputs: HashMap<PathBuf, String>,
+}
+
+impl Results {
+    /// Stores the output of the file, and returns true iff it differs from its previous output.
+    fn update(&mut self, file: PathBuf, output: String) -> bool {
+        self.outputs
+            .insert(file, output.clone())
+            .is_none_or(|previous| previous != output)
+    }
+
+    /// Forgets the output of a removed file, so that its result is printed again when it is created.
+    fn remove(&mut self, file: &Path) {
+        self.outputs.remove(file);
+    }
+}
+
+/// Returns the changed files that must be checked again, relative to the current directory when they are within it.
+///
+/// Removed files are forgotten by the results, and files that are not included by the configuration are skipped,
+/// unless they were named explicitly.
+fn files_to_check(
+    changed: BTreeSet<PathBuf>,
+    current_dir: &Path,
+    explicit: &HashSet<PathBuf>,
+    config: &Config,
+    results: &mut Results,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for path in changed {
+        let file = path.strip_prefix(current_dir).map_or(path.clone(), Path::to_path_buf);
+        if !path.is_file() {
+            results.remove(&file);
+        } else if explicit.contains(&path) || config.is_included(&file) {
+            files.push(file);
+        }
+    }
+
+    files
+}
+
+/// Adds the paths of the event when it changes the contents of files.
+fn add_paths(changed: &mut BTreeSet<PathBuf>, event: notify::Event) {
+    if matches!(
+        event.kind,
+        EventKind::Any | EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        changed.extend(event.paths);
+    }
+}
+
+/// Returns the plain text output for the reports of a single file.
+fn render(options: &DiffOptions, reports: &[report::FileReport]) -> Result<String, Box<dyn Error>> {
+    let mut output = Vec::new();
+    for report in reports {
+        report::write_file_text(&mut output, options, report)?;
+    }
+
+    Ok(String::from_utf8(output)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results() {
+        let mut results = Results::default();
+        assert!(results.update(PathBuf::from("deadlock.mcf"), "deadlock.mcf: identical\n".to_string()));
+        assert!(!results.update(PathBuf::from("deadlock.mcf"), "deadlock.mcf: identical\n".to_string()));
+        assert!(results.update(PathBuf::from("deadlock.mcf"), "deadlock.mcf: parse error\n".to_string()));
+
+        // A removed file is printed again when it is created.
+        results.remove(Path::new("deadlock.mcf"));
+        assert!(results.update(PathBuf::from("deadlock.mcf"), "deadlock.mcf: parse error\n".to_string()));
+    }
+
+    #[test]
+    fn test_files_to_check() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = |name: &str| directory.path().join(name);
+        for name in ["deadlock.mcf", "notes.txt", "property.txt"] {
+            fs::write(path(name), "").unwrap();
+        }
+
+        let mut results = Results::default();
+        results.update(PathBuf::from("removed.mcf"), "removed.mcf: identical\n".to_string());
+
+        let changed = BTreeSet::from(["deadlock.mcf", "notes.txt", "property.txt", "removed.mcf"].map(path));
+        let explicit = HashSet::from([path("property.txt")]);
+        assert_eq!(
+            files_to_check(changed, directory.path(), &explicit, &Config::default(), &mut results),
+            vec![PathBuf::from("deadlock.mcf"), PathBuf::from("property.txt")]
+        );
+
+        // The removed file is forgotten, so its result is printed again when it is created.
+        assert!(results.update(PathBuf::from("removed.mcf"), "removed.mcf: identical\n".to_string()));
+    }
+}
@@ -0,0 +1,254 @@
+//! The parsers of the releases of mCRL2 that can be compared.
+//!
+//! Every parser is a [ParserBackend] with the version label of its release as
+//! name. The 202507.0 release is linked into the current process, while other
+//! releases are run as a helper executable, such as `mcrl2-2024`, since the
+//! libraries of different releases cannot be linked into one process.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::Checker;
+use crate::InputKind;
+use crate::find_executable;
+use crate::find_helper;
+use crate::helper::HelperProcess;
+use crate::print_ast_2025;
+use crate::run_helper;
+
+/// The name of the 202301.0 release, which is parsed by the mcrl2-2023 helper.
+pub const MCRL2_2023_RELEASE: &str = "202301.0";
+
+/// The name of the 202407.1 release, which is parsed by the mcrl2-2024 helper.
+pub const PREVIOUS_RELEASE: &str = "202407.1";
+
+/// The name of the 202507.0 release, which is linked by mcrl2-sys.
+pub const CURRENT_RELEASE: &str = "202507.0";
+
+/// A parser that prints the fully parenthesized AST of its release.
+pub trait ParserBackend: Send + Sync {
+    /// Returns the name of the backend, which is used to select it.
+    fn name(&self) -> &str;
+
+    /// Returns the version label of the release, which is shown in the headers of differences.
+    fn version(&self) -> &str {
+        self.name()
+    }
+
+    /// Prints the AST of an mCRL2 specification or modal formula, where parse errors are a [crate::ParseError].
+    fn print_ast(&self, input: &str, mcf: bool) -> Result<String, Box<dyn Error>>;
+
+    /// Prints the AST of an input of the given kind.
+    ///
+    /// There is no parser for data expressions on their own, so they are parsed as the
+    /// formula `val(...)`, and the data expression is taken from the printed formula.
+    fn parse(&self, input: &str, kind: InputKind) -> Result<String, Box<dyn Error>> {
+        match kind {
+            InputKind::Mcrl2 => self.print_ast(input, false),
+            InputKind::Mcf => self.print_ast(input, true),
+            InputKind::DataExpression => {
+                Ok(data_expression(&self.print_ast(&format!("val({})", input), true)?).to_string())
+            }
+        }
+    }
+}
+
+/// A function that prints the AST of an mCRL2 specification, or of a modal formula when the flag is set.
+pub type PrintAst = fn(&str, bool) -> Result<String, Box<dyn Error>>;
+
+/// A parser that is linked into the current process.
+pub struct InProcessBackend {
+    name: String,
+    print_ast: PrintAst,
+}
+
+impl InProcessBackend {
+    /// Returns a backend with the given name that parses with the given function.
+    pub fn new(name: impl Into<String>, print_ast: PrintAst) -> Self {
+        Self {
+            name: name.into(),
+            print_ast,
+        }
+    }
+
+    /// Returns the backend of the 202507.0 release.
+    pub fn mcrl2_2025() -> Self {
+        Self::new(CURRENT_RELEASE, print_ast_2025)
+    }
+}
+
+impl ParserBackend for InProcessBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn print_ast(&self, input: &str, mcf: bool) -> Result<String, Box<dyn Error>> {
+        (self.print_ast)(input, mcf)
+    }
+}
+
+/// A parser that runs a helper executable with the same command line interface as mcrl2-2024.
+///
+/// The helper reads the input from stdin, parses a modal formula when `--mcf` is
+/// given and prints the AST, where a parse error results in exit code 1.
+pub struct SubprocessBackend {
+    name: String,
+
+    /// The version label of the release, where None uses the name.
+    version: Option<String>,
+
+    /// The location of the helper, where None searches for mcrl2-2024 in PATH and next to the current executable.
+    path: Option<PathBuf>,
+
+    /// The helper process that is reused for all inputs, only when [SubprocessBackend::with_persistent_helper] is used.
+    helper: Option<Mutex<Option<HelperProcess>>>,
+}
+
+impl SubprocessBackend {
+    /// Returns a backend with the given name that runs the helper at the given path, or the mcrl2-2024 helper for None.
+    pub fn new(name: impl Into<String>, path: Option<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            version: None,
+            path,
+            helper: None,
+        }
+    }
+
+    /// Shows the given version label in the headers of differences, instead of the name.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Reuses one helper process for all inputs, which is started on first use and requires the `--server` mode of mcrl2-2024.
+    pub fn with_persistent_helper(mut self) -> Self {
+        self.helper = Some(Mutex::new(None));
+        self
+    }
+}
+
+impl ParserBackend for SubprocessBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        self.version.as_deref().unwrap_or(&self.name)
+    }
+
+    fn print_ast(&self, input: &str, mcf: bool) -> Result<String, Box<dyn Error>> {
+        match &self.path {
+            Some(path) => run_helper(&self.name, path, self.helper.as_ref(), input, mcf),
+            None => run_helper(&self.name, &find_helper()?, self.helper.as_ref(), input, mcf),
+        }
+    }
+}
+
+/// The backends that can be compared, by their name.
+pub struct Registry {
+    backends: Vec<Arc<dyn ParserBackend>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registry {
+    /// Returns a registry with the 202407.1 and 202507.0 releases, and the 202301.0 release when its helper is found.
+    pub fn new() -> Self {
+        Self::from_checker(&Checker::new())
+    }
+
+    /// Returns a registry with the previous and current backends of the checker.
+    ///
+    /// The 202301.0 release is registered first when the mcrl2-2023 helper is found in
+    /// PATH or next to the current executable, since it is only built on request.
+    pub fn from_checker(checker: &Checker) -> Self {
+        let mut backends: Vec<Arc<dyn ParserBackend>> = Vec::new();
+        if let Ok(path) = find_executable("mcrl2-2023") {
+            backends.push(Arc::new(SubprocessBackend::new(MCRL2_2023_RELEASE, Some(path))));
+        }
+
+        backends.extend([checker.previous().clone(), checker.current().clone()]);
+        Self { backends }
+    }
+
+    /// Adds a backend, whose name must differ from the registered backends.
+    pub fn register(&mut self, backend: Arc<dyn ParserBackend>) -> Result<(), Box<dyn Error>> {
+        if self.backends.iter().any(|registered| registered.name() == backend.name()) {
+            return Err(format!("A backend named {} is already registered", backend.name()).into());
+        }
+
+        self.backends.push(backend);
+        Ok(())
+    }
+
+    /// Returns the registered backends, in the order in which they are registered.
+    pub fn backends(&self) -> &[Arc<dyn ParserBackend>] {
+        &self.backends
+    }
+
+    /// Returns the backend with the given name.
+    pub fn get(&self, name: &str) -> Result<Arc<dyn ParserBackend>, Box<dyn Error>> {
+        self.backends
+            .iter()
+            .find(|backend| backend.name() == name)
+            .cloned()
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.backends.iter().map(|backend| backend.name()).collect();
+                format!("Unknown backend {}, the registered backends are {}", name, names.join(", ")).into()
+            })
+    }
+
+    /// Returns a checker that compares the backend named `from` (previous) to the backend named `to` (current).
+    pub fn checker(&self, from: &str, to: &str) -> Result<Checker, Box<dyn Error>> {
+        Ok(Checker::between(self.get(from)?, self.get(to)?))
+    }
+}
+
+/// Returns the data expression of the printed formula `val(...)`.
+fn data_expression(ast: &str) -> &str {
+    let formula = ast.trim();
+    let formula = formula.strip_prefix("form").unwrap_or(formula);
+    let formula = formula.strip_suffix(';').unwrap_or(formula).trim();
+
+    formula
+        .strip_prefix("val(")
+        .and_then(|expression| expression.strip_suffix(')'))
+        .unwrap_or(formula)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_expression() {
+        assert_eq!(data_expression("form val((x) && ((y) => (z)));\n"), "(x) && ((y) => (z))");
+        assert_eq!(data_expression("val(f(x))"), "f(x)");
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::new();
+        assert!(registry.get(PREVIOUS_RELEASE).is_ok());
+        assert!(registry.register(Arc::new(InProcessBackend::mcrl2_2025())).is_err());
+
+        registry
+            .register(Arc::new(SubprocessBackend::new("fork", Some(PathBuf::from("mcrl2-fork")))))
+            .unwrap();
+        registry
+            .register(Arc::new(
+                SubprocessBackend::new("next", Some(PathBuf::from("mcrl2-next"))).with_version("202607.0"),
+            ))
+            .unwrap();
+        assert_eq!(registry.get("next").unwrap().version(), "202607.0");
+        assert_eq!(registry.get("fork").unwrap().version(), "fork");
+        assert!(registry.get("202301").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! The public interface for checking inputs, which compares the ASTs of two parser backends.
+//!
+//! A [Checker] does not print anything: [Checker::check] returns the outcome
+//! together with the ASTs or errors of both releases, and [Checker::compare]
+//! returns a [Comparison] whose differences can be printed by the caller.
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::Comparison;
+use crate::InProcessBackend;
+use crate::Outcome;
+use crate::PREVIOUS_RELEASE;
+use crate::ParserBackend;
+use crate::SubprocessBackend;
+
+/// The kind of an input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    /// An mCRL2 specification.
+    Mcrl2,
+
+    /// A modal formula, optionally with the declarations of a state formula specification.
+    Mcf,
+
+    /// A data expression.
+    DataExpression,
+}
+
+impl InputKind {
+    /// Returns the kind of the mcf flag that is used by the functions of this crate.
+    pub fn from_mcf(mcf: bool) -> InputKind {
+        if mcf { InputKind::Mcf } else { InputKind::Mcrl2 }
+    }
+}
+
+/// The result of checking an input with both releases.
+#[derive(Debug)]
+pub struct CheckOutcome {
+    /// The outcome of the check, where differences that are suppressed by a comment count as identical.
+    pub outcome: Outcome,
+
+    /// The AST printed by the 202407.1 release, or the error when it cannot parse the input.
+    pub previous: Result<String, Box<dyn Error>>,
+
+    /// The AST printed by the 202507.0 release, or the error when it cannot parse the input.
+    pub current: Result<String, Box<dyn Error>>,
+}
+
+/// Checks whether inputs are parsed differently by two releases, by default 202407.1 (previous) and 202507.0 (current).
+///
+/// The 202507.0 release is linked into the checker, while the 202407.1 release is
+/// run by the mcrl2-2024 helper executable, for example:
+///
+/// ```no_run
+/// use parse_checker_core::Checker;
+/// use parse_checker_core::InputKind;
+///
+/// let checker = Checker::with_persistent_helper(None);
+/// let outcome = checker.check("[true*]<true>true", InputKind::Mcf);
+/// println!("{:?}", outcome.outcome);
+/// ```
+#[derive(Clone)]
+pub struct Checker {
+    /// The backend of the previous release.
+    previous: Arc<dyn ParserBackend>,
+
+    /// The backend of the current release.
+    current: Arc<dyn ParserBackend>,
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Checker {
+    /// Returns a checker that compares 202407.1 to 202507.0, which starts the mcrl2-2024 helper for every input.
+    pub fn new() -> Checker {
+        Checker::between(
+            Arc::new(SubprocessBackend::new(PREVIOUS_RELEASE, None)),
+            Arc::new(InProcessBackend::mcrl2_2025()),
+        )
+    }
+
+    /// Returns a checker that compares 202407.1 to 202507.0, which reuses one mcrl2-2024 process at the given path for all inputs.
+    ///
+    /// The helper is searched for in PATH and next to the current executable when no path is given.
+    pub fn with_persistent_helper(helper_path: Option<PathBuf>) -> Checker {
+        Checker::between(
+            Arc::new(SubprocessBackend::new(PREVIOUS_RELEASE, helper_path).with_persistent_helper()),
+            Arc::new(InProcessBackend::mcrl2_2025()),
+        )
+    }
+
+    /// Returns a checker that compares the previous backend to the current backend.
+    pub fn between(previous: Arc<dyn ParserBackend>, current: Arc<dyn ParserBackend>) -> Checker {
+        Checker { previous, current }
+    }
+
+    /// Returns the backend of the previous release.
+    pub fn previous(&self) -> &Arc<dyn ParserBackend> {
+        &self.previous
+    }
+
+    /// Returns the backend of the current release.
+    pub fn current(&self) -> &Arc<dyn ParserBackend> {
+        &self.current
+    }
+
+    /// Prints the AST of the input using the previous release.
+    pub fn print_ast_previous(&self, input: &str, kind: InputKind) -> Result<String, Box<dyn Error>> {
+        self.previous.parse(input, kind)
+    }
+
+    /// Prints the AST of the input using the current release.
+    pub fn print_ast_current(&self, input: &str, kind: InputKind) -> Result<String, Box<dyn Error>> {
+        self.current.parse(input, kind)
+    }
+
+    /// Parses the input with both releases, and fails when one of them cannot parse it.
+    pub fn compare(&self, input: &str, kind: InputKind) -> Result<Comparison, Box<dyn Error>> {
+        Ok(Comparison {
+            current: self.print_ast_current(input, kind)?,
+            previous: self.print_ast_previous(input, kind)?,
+        })
+    }
+
+    /// Parses the input with both releases, where the errors of both releases are part of the outcome.
+    pub fn check(&self, input: &str, kind: InputKind) -> CheckOutcome {
+        let previous = self.print_ast_previous(input, kind);
+        let current = self.print_ast_current(input, kind);
+
+        let outcome = match (&previous, &current) {
+            (Ok(previous), Ok(current)) => Comparison {
+                current: current.clone(),
+                previous: previous.clone(),
+            }
+            .outcome(input),
+            (previous, current) => [previous, current]
+                .into_iter()
+                .filter_map(|result| result.as_ref().err())
+                .map(|error| Outcome::of_error(error.as_ref()))
+                .max()
+                .expect("one of the releases failed"),
+        };
+
+        CheckOutcome {
+            outcome,
+            previous,
+            current,
+        }
+    }
+}
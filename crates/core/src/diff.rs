@@ -0,0 +1,599 @@
+
+use duct::cmd;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use console::Style;
+use serde::Serialize;
+use similar::ChangeTag;
+use similar::DiffTag;
+use similar::TextDiff;
+
+use crate::CURRENT_RELEASE;
+use crate::Layout;
+use crate::helper::HelperProcess;
+use crate::Rule;
+use crate::statements::Statement;
+use crate::statements::split_statements;
+
+/// The error returned when one of the releases cannot parse the input.
+#[derive(Debug)]
+pub struct ParseError {
+    /// The name of the release that cannot parse the input.
+    pub release: String,
+
+    /// The error message of the parser.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mCRL2 {} cannot parse the input: {}", self.release, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// The error returned when a modal formula cannot be type checked against a specification.
+#[derive(Debug)]
+pub struct TypeCheckError {
+    /// The error message of the type checker.
+    pub message: String,
+}
+
+impl fmt::Display for TypeCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mCRL2 202507.0 cannot type check the formula against the specification: {}", self.message)
+    }
+}
+
+impl Error for TypeCheckError {}
+
+/// The outcome of checking an input, ordered by severity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    /// Both releases produce the same AST.
+    Identical,
+
+    /// The releases produce different ASTs.
+    Difference,
+
+    /// One of the releases cannot parse the input, or a property cannot be type checked.
+    ParseError,
+
+    /// The check could not be performed, for example because the mcrl2-2024 helper cannot be run.
+    InternalError,
+}
+
+impl Outcome {
+    /// Returns the outcome of a comparison, see [crate::Checker::compare].
+    pub fn of(result: &Result<Comparison, Box<dyn Error>>) -> Outcome {
+        match result {
+            Ok(comparison) if comparison.is_identical() => Outcome::Identical,
+            Ok(_) => Outcome::Difference,
+            Err(error) => Outcome::of_error(error.as_ref()),
+        }
+    }
+
+    /// Returns the outcome for an error that occurred while checking an input.
+    pub fn of_error(error: &(dyn Error + 'static)) -> Outcome {
+        if error.is::<ParseError>() || error.is::<TypeCheckError>() {
+            Outcome::ParseError
+        } else {
+            Outcome::InternalError
+        }
+    }
+}
+
+/// Prints the AST of an mCRL2 specification or modal formula using the 202507.0 release.
+pub(crate) fn print_ast_2025(input: &str, mcf: bool) -> Result<String, Box<dyn Error>> {
+    let result = if mcf {
+        mcrl2_sys::print_ast_mcf(input)
+    } else {
+        mcrl2_sys::print_ast_mcrl2(input)
+    };
+
+    Ok(result.map_err(|e| ParseError {
+        release: CURRENT_RELEASE.to_string(),
+        message: e.to_string(),
+    })?)
+}
+
+/// Type checks a modal formula against an mCRL2 specification using the 202507.0 release.
+pub fn type_check_2025(specification: &str, formula: &str) -> Result<(), Box<dyn Error>> {
+    Ok(mcrl2_sys::type_check_mcf(specification, formula).map_err(|e| TypeCheckError { message: e.to_string() })?)
+}
+
+/// Prints the AST using the helper at the given path, where the persistent helper is used when it is given.
+///
+/// The release is the name of the backend in errors.
+pub(crate) fn run_helper(
+    release: &str,
+    mcrl2_path: &Path,
+    persistent: Option<&Mutex<Option<HelperProcess>>>,
+    input: &str,
+    mcf: bool,
+) -> Result<String, Box<dyn Error>> {
+    // Check if the executables exist
+    if !mcrl2_path.exists() {
+        return Err(format!(
+            "Cannot find the executable of backend {} at {}",
+            release,
+            mcrl2_path.display()
+        )
+        .into());
+    }
+
+    if let Some(persistent) = persistent {
+        let mut guard = persistent.lock().unwrap();
+        let helper = match &mut *guard {
+            Some(helper) => helper,
+            None => guard.insert(HelperProcess::spawn(mcrl2_path)?),
+        };
+
+        return match helper.print_ast(input, mcf) {
+            Ok(Ok(ast)) => Ok(ast),
+            Ok(Err(message)) => Err(ParseError {
+                release: release.to_string(),
+                message,
+            })?,
+            Err(error) => {
+                // The helper is restarted for the next input.
+                *guard = None;
+                Err(format!("The helper {} failed: {}", mcrl2_path.display(), error))?
+            }
+        };
+    }
+
+    let mut arguments: Vec<String> = Vec::new();
+    if mcf {
+        arguments.push("--mcf".into());
+    }
+
+    let tool = cmd(mcrl2_path, arguments)
+        .stdin_bytes(input)
+        .stderr_capture()
+        .stdout_capture()
+        .unchecked()
+        .run()?;
+
+    // The helper reports a parse error by returning an error from main, which results in exit code 1.
+    match tool.status.code() {
+        Some(0) => Ok(String::from_utf8(tool.stdout)?),
+        Some(1) => Err(ParseError {
+            release: release.to_string(),
+            message: String::from_utf8_lossy(&tool.stderr)
+                .trim()
+                .trim_start_matches("Error: ")
+                .to_string(),
+        })?,
+        _ => Err(format!(
+            "The helper {} failed ({}): {}",
+            mcrl2_path.display(),
+            tool.status,
+            String::from_utf8_lossy(&tool.stderr).trim()
+        ))?,
+    }
+}
+
+/// Searches for the mcrl2-2024 helper in PATH and next to the current executable.
+pub(crate) fn find_helper() -> Result<PathBuf, Box<dyn Error>> {
+    find_executable("mcrl2-2024")
+}
+
+/// Searches for the executable with the given name in PATH and next to the current executable.
+pub(crate) fn find_executable(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let mcrl2_path = which::which(name)
+        .or_else(|_| {
+            // Try to find the executable in the same directory as the current executable
+            std::env::current_exe()
+                .map_err(|_e| which::Error::CannotFindBinaryPath)
+                .and_then(|mut path| {
+                    path.pop(); // Remove the executable name
+                    
+                    // Try removing "deps" directory if it's the last component
+                    if path.file_name().and_then(|s| s.to_str()) == Some("deps") {
+                        path.pop();
+                    }
+
+                    path.push(if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() });
+                    if path.exists() {
+                        Ok(path)
+                    } else {
+                        Err(which::Error::CannotFindBinaryPath)
+                    }
+                })
+        })
+        .map_err(|_| format!("Cannot find the {} executable in PATH or next to the parse-checker executable", name))?;
+
+    Ok(mcrl2_path)
+}
+
+/// The label of the 202407.1 release in the printed differences.
+pub const PREVIOUS_LABEL: &str = "mCRL2 202407.1";
+
+/// The label of the 202507.0 release in the printed differences.
+pub const CURRENT_LABEL: &str = "mCRL2 202507.0";
+
+/// The layout in which the differences between two ASTs are printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// Prints both ASTs next to each other in two columns.
+    #[default]
+    SideBySide,
+
+    /// Prints a unified line diff of both ASTs.
+    Unified,
+}
+
+/// The options for printing the differences between two ASTs.
+#[derive(Clone, Copy, Debug)]
+pub struct DiffOptions {
+    /// The layout of the differences.
+    pub format: DiffFormat,
+
+    /// Whether the output is coloured.
+    pub color: bool,
+
+    /// The total width available for the output.
+    pub width: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            format: DiffFormat::default(),
+            color: false,
+            width: 120,
+        }
+    }
+}
+
+/// Prints the differences between the ASTs of the 202407.1 (previous) and 202507.0 (current) releases.
+pub fn print_diff(f: &mut impl Write, options: &DiffOptions, previous: &str, current: &str) -> std::io::Result<()> {
+    print_labelled_diff(f, options, (PREVIOUS_LABEL, CURRENT_LABEL), previous, current)
+}
+
+/// Prints the differences between the ASTs of two releases, where the labels name the (previous, current) releases.
+pub fn print_labelled_diff(
+    f: &mut impl Write,
+    options: &DiffOptions,
+    labels: (&str, &str),
+    previous: &str,
+    current: &str,
+) -> std::io::Result<()> {
+    match options.format {
+        DiffFormat::SideBySide => print_side_by_side(f, options, labels, previous, current),
+        DiffFormat::Unified => print_unified(f, options, labels, previous, current),
+    }
+}
+
+/// Prints both ASTs in two columns, where the changed lines are marked in between.
+fn print_side_by_side(
+    f: &mut impl Write,
+    options: &DiffOptions,
+    (previous_label, current_label): (&str, &str),
+    previous: &str,
+    current: &str,
+) -> std::io::Result<()> {
+    let column = (options.width.saturating_sub(3) / 2).max(20);
+    let layout = Layout { width: column, indent: 2 };
+    let previous = layout.format(previous);
+    let current = layout.format(current);
+
+    let bold = Style::new().bold().force_styling(options.color);
+    writeln!(f, "{}   {}", bold.apply_to(format!("{previous_label:column$}")), bold.apply_to(current_label))?;
+    writeln!(f, "{:-<column$}   {:-<column$}", "", "")?;
+
+    let diff = TextDiff::from_lines(&previous, &current);
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let (marker, left_style, right_style) = match tag {
+            DiffTag::Equal => (' ', Style::new(), Style::new()),
+            DiffTag::Delete => ('<', Style::new().red(), Style::new()),
+            DiffTag::Insert => ('>', Style::new(), Style::new().green()),
+            DiffTag::Replace => ('|', Style::new().red(), Style::new().green()),
+        };
+        let left_style = left_style.force_styling(options.color);
+        let right_style = right_style.force_styling(options.color);
+
+        let left = &diff.old_slices()[old_range];
+        let right = &diff.new_slices()[new_range];
+        for index in 0..left.len().max(right.len()) {
+            // Lines that do not fit in a column are wrapped.
+            let left = wrap(left.get(index).map_or("", |line| line.trim_end()), column);
+            let right = wrap(right.get(index).map_or("", |line| line.trim_end()), column);
+
+            for row in 0..left.len().max(right.len()) {
+                let left = left.get(row).copied().unwrap_or("");
+                let right = right.get(row).copied().unwrap_or("");
+                let padding = column - left.chars().count();
+
+                writeln!(
+                    f,
+                    "{}{:padding$} {} {}",
+                    left_style.apply_to(left),
+                    "",
+                    marker,
+                    right_style.apply_to(right)
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints a unified diff of both ASTs.
+fn print_unified(
+    f: &mut impl Write,
+    options: &DiffOptions,
+    (previous_label, current_label): (&str, &str),
+    previous: &str,
+    current: &str,
+) -> std::io::Result<()> {
+    let layout = Layout {
+        width: options.width,
+        indent: 2,
+    };
+    let previous = layout.format(previous);
+    let current = layout.format(current);
+
+    let delete = Style::new().red().force_styling(options.color);
+    let insert = Style::new().green().force_styling(options.color);
+    let header = Style::new().cyan().force_styling(options.color);
+    writeln!(f, "{}", delete.apply_to(format!("--- {previous_label}")).bold())?;
+    writeln!(f, "{}", insert.apply_to(format!("+++ {current_label}")).bold())?;
+
+    let diff = TextDiff::from_lines(&previous, &current);
+    for hunk in diff.unified_diff().context_radius(3).iter_hunks() {
+        writeln!(f, "{}", header.apply_to(hunk.header()))?;
+
+        for change in hunk.iter_changes() {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ("-", &delete),
+                ChangeTag::Insert => ("+", &insert),
+                ChangeTag::Equal => (" ", &Style::new()),
+            };
+
+            writeln!(f, "{}", style.apply_to(format!("{}{}", sign, change.value().trim_end())))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits the line into parts of at most the given number of characters.
+fn wrap(line: &str, width: usize) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut rest = line;
+
+    while rest.chars().count() > width {
+        let (index, _) = rest.char_indices().nth(width).expect("the line has more than width characters");
+        result.push(&rest[..index]);
+        rest = &rest[index..];
+    }
+
+    result.push(rest);
+    result
+}
+
+/// The ASTs of an input as parsed by both releases of mCRL2.
+pub struct Comparison {
+    /// The AST printed by the 202507.0 release.
+    pub current: String,
+
+    /// The AST printed by the 202407.1 release.
+    pub previous: String,
+}
+
+/// A top-level statement that is parsed differently by both releases.
+pub struct Difference<'a> {
+    /// The corresponding statement in the input, if it could be determined.
+    pub original: Option<Statement<'a>>,
+
+    /// The statement as parsed by the 202507.0 release.
+    pub current: &'a str,
+
+    /// The statement as parsed by the 202407.1 release.
+    pub previous: &'a str,
+}
+
+impl Difference<'_> {
+    /// Returns the precedence rule that caused the difference.
+    pub fn rule(&self) -> Rule {
+        Rule::classify(self.previous, self.current)
+    }
+
+    /// Returns true iff the difference is suppressed by a comment in the input, see [Statement::is_suppressed].
+    pub fn is_suppressed(&self) -> bool {
+        self.original
+            .as_ref()
+            .is_some_and(|original| original.is_suppressed(self.rule().id()))
+    }
+}
+
+impl Comparison {
+    /// Returns true iff both releases produce the same AST.
+    pub fn is_identical(&self) -> bool {
+        self.current == self.previous
+    }
+
+    /// Returns the outcome for the given input, where differences that are suppressed by a comment count as identical.
+    pub fn outcome(&self, input: &str) -> Outcome {
+        if self.differences(input).iter().all(|difference| difference.is_suppressed()) {
+            Outcome::Identical
+        } else {
+            Outcome::Difference
+        }
+    }
+
+    /// Returns the top-level statements of the given input that are parsed differently.
+    ///
+    /// The statements of both ASTs are matched by position, and to the statements of the input by
+    /// their key, see [match_statements], since the sections may be printed in a different order.
+    pub fn differences<'a>(&'a self, input: &'a str) -> Vec<Difference<'a>> {
+        if self.is_identical() {
+            return Vec::new();
+        }
+
+        let current = split_statements(&self.current);
+        let previous = split_statements(&self.previous);
+        let original = split_statements(input);
+
+        if current.len() != previous.len() {
+            // The statements cannot be matched, so report the whole input as a single difference.
+            return vec![Difference {
+                original: (original.len() == 1).then(|| original[0].clone()),
+                current: self.current.trim(),
+                previous: self.previous.trim(),
+            }];
+        }
+
+        let matches = match_statements(&original, &current);
+        current
+            .iter()
+            .zip(previous.iter())
+            .zip(matches)
+            .filter(|((current, previous), _)| current.text != previous.text)
+            .map(|((current, previous), original)| Difference {
+                original,
+                current: current.text,
+                previous: previous.text,
+            })
+            .collect()
+    }
+
+    /// Returns the statements of the input with a directive that cannot be matched to a statement of the ASTs.
+    ///
+    /// These directives have no effect, so they should be reported as a warning.
+    pub fn unattached_directives<'a>(&self, input: &'a str) -> Vec<Statement<'a>> {
+        if self.is_identical() {
+            return Vec::new();
+        }
+
+        let original = split_statements(input);
+        let matches = match_statements(&original, &split_statements(&self.current));
+        original
+            .into_iter()
+            .filter(|statement| {
+                statement.has_directive()
+                    && !matches.iter().flatten().any(|matched| matched.range == statement.range)
+            })
+            .collect()
+    }
+}
+
+/// Returns the statement of the input that corresponds to every statement of the AST.
+///
+/// The n-th statement of the AST with a given [Statement::key] corresponds to the n-th statement
+/// of the input with that key, but only when both contain the same number of statements with it.
+fn match_statements<'a>(input: &[Statement<'a>], ast: &[Statement]) -> Vec<Option<Statement<'a>>> {
+    let mut by_key: HashMap<_, Vec<&Statement<'a>>> = HashMap::new();
+    for statement in input {
+        by_key.entry(statement.key()).or_default().push(statement);
+    }
+
+    let mut counts: HashMap<_, usize> = HashMap::new();
+    for statement in ast {
+        *counts.entry(statement.key()).or_default() += 1;
+    }
+
+    let mut seen: HashMap<_, usize> = HashMap::new();
+    ast.iter()
+        .map(|statement| {
+            let key = statement.key();
+            let index = seen.entry(key).or_default();
+            *index += 1;
+
+            by_key
+                .get(&key)
+                .filter(|statements| statements.len() == counts[&key])
+                .map(|statements| statements[*index - 1].clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_helper() {
+        let error = run_helper("fork", Path::new("tools/missing-mcrl2-fork"), None, "true", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot find the executable of backend fork at tools/missing-mcrl2-fork"
+        );
+    }
+
+    #[test]
+    fn test_print_side_by_side() {
+        let options = DiffOptions {
+            format: DiffFormat::SideBySide,
+            color: false,
+            width: 43,
+        };
+
+        let mut output = Vec::new();
+        print_diff(&mut output, &options, "form (a) && ((b) => (c));", "form ((a) && (b)) => (c);").unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "mCRL2 202407.1         mCRL2 202507.0\n\
+             --------------------   --------------------\n\
+             form (a) &&          | form ((a) && (b)) =>\n\
+             ((b) => (c));        | (c);\n"
+        );
+    }
+
+    #[test]
+    fn test_print_unified() {
+        let options = DiffOptions {
+            format: DiffFormat::Unified,
+            color: false,
+            width: 80,
+        };
+
+        let mut output = Vec::new();
+        print_diff(&mut output, &options, "form (a) && ((b) => (c));", "form ((a) && (b)) => (c);").unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "--- mCRL2 202407.1\n+++ mCRL2 202507.0\n@@ -1 +1 @@\n-form (a) && ((b) => (c));\n+form ((a) && (b)) => (c);\n"
+        );
+    }
+
+    #[test]
+    fn test_differences_out_of_order() {
+        // The processes are declared before the actions, but are printed after them.
+        let input = "proc P = a . b + c;\n% parse-checker: ignore\nact a, b, c;\ninit P;\n";
+        let comparison = Comparison {
+            previous: "act  a,b,c;\n\nproc P = (a) . ((b) + (c));\n\ninit P;\n".to_string(),
+            current: "act  a,b,c;\n\nproc P = ((a) . (b)) + (c);\n\ninit P;\n".to_string(),
+        };
+
+        let differences = comparison.differences(input);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].original.as_ref().unwrap().text, "proc P = a . b + c");
+        assert!(!differences[0].is_suppressed());
+        assert_eq!(comparison.outcome(input), Outcome::Difference);
+        assert!(comparison.unattached_directives(input).is_empty());
+
+        // The directive is attached to the process, regardless of its position.
+        let input = "% parse-checker: ignore\nproc P = a . b + c;\nact a, b, c;\ninit P;\n";
+        assert!(comparison.differences(input)[0].is_suppressed());
+        assert_eq!(comparison.outcome(input), Outcome::Identical);
+
+        // The processes cannot be matched when the AST contains a different number of them.
+        let input = "% parse-checker: ignore\nproc P = a . b + c;\nP = a;\nact a, b, c;\ninit P;\n";
+        assert!(comparison.differences(input)[0].original.is_none());
+        assert_eq!(comparison.outcome(input), Outcome::Difference);
+        assert_eq!(comparison.unattached_directives(input)[0].text, "proc P = a . b + c");
+    }
+}
@@ -0,0 +1,69 @@
+//! A persistent mcrl2-2024 helper process that parses many inputs.
+//!
+//! The helper is started with `--server`, after which it answers requests with the
+//! protocol of [mcrl2_helper].
+
+use std::io;
+use std::io::BufReader;
+use std::path::Path;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::ChildStdout;
+use std::process::Command;
+use std::process::Stdio;
+
+use mcrl2_helper::read_message;
+use mcrl2_helper::write_message;
+
+/// A running mcrl2-2024 helper in server mode.
+pub(crate) struct HelperProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl HelperProcess {
+    /// Starts the helper at the given path.
+    pub(crate) fn spawn(path: &Path) -> io::Result<HelperProcess> {
+        let mut child = Command::new(path)
+            .arg("--server")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        Ok(HelperProcess { child, stdin, stdout })
+    }
+
+    /// Returns the AST of the input, or the error message of the parser when it cannot be parsed.
+    pub(crate) fn print_ast(&mut self, input: &str, mcf: bool) -> io::Result<Result<String, String>> {
+        write_message(&mut self.stdin, if mcf { "mcf" } else { "mcrl2" }, input)?;
+
+        let Some((status, output)) = read_message(&mut self.stdout)? else {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the mcrl2-2024 helper stopped unexpectedly",
+            ));
+        };
+
+        match status.as_str() {
+            "ok" => Ok(Ok(output)),
+            "error" => Ok(Err(output)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid response status {} of the mcrl2-2024 helper", status),
+            )),
+        }
+    }
+}
+
+impl Drop for HelperProcess {
+    fn drop(&mut self) {
+        // The helper might be busy with an input that takes long to parse, so it is not enough to close stdin.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
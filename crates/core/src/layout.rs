@@ -0,0 +1,304 @@
+//! A width aware layout printer for the (fully parenthesized) ASTs printed by mCRL2.
+//!
+//! The AST is first split into nested bracket groups, which are then printed using
+//! the algorithm of Wadler's "A prettier printer": a group is printed on a single
+//! line whenever it fits within the line width, and otherwise its contents are
+//! indented and broken after the binary operators and binders.
+
+use std::fmt::Write;
+
+/// The operators after which a line may be broken.
+const BREAK_AFTER: [&str; 15] = [
+    "&&", "||", "=>", "+", ".", "<>", "->", "|", "||_", "|>", "<<", "=", "-", "*", "whr",
+];
+
+/// The keywords that bind variables up to the end of the enclosing group, whose body is indented when broken.
+const BINDERS: [&str; 6] = ["forall", "exists", "nu", "mu", "lambda", "sum"];
+
+/// The layout options of the printer.
+#[derive(Clone, Copy, Debug)]
+pub struct Layout {
+    /// The maximum number of characters on a line, which is only exceeded when a line cannot be broken.
+    pub width: usize,
+
+    /// The number of spaces that the contents of a bracket group are indented by.
+    pub indent: usize,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self { width: 100, indent: 2 }
+    }
+}
+
+impl Layout {
+    /// Formats the given AST according to the layout options.
+    pub fn format(&self, ast: &str) -> String {
+        let tokens = tokenize(ast);
+        let docs = build_statements(&tokens);
+
+        let mut output = String::new();
+        let mut column = 0;
+        for doc in &docs {
+            self.render(&mut output, doc, 0, false, &mut column);
+        }
+
+        // Ensure that the output is terminated by exactly one newline.
+        let len = output.trim_end().len();
+        output.truncate(len);
+        output.push('\n');
+        output
+    }
+
+    /// Renders the document, where `flat` indicates that line breaks must be printed as spaces.
+    fn render(&self, output: &mut String, doc: &Doc, indent: usize, flat: bool, column: &mut usize) {
+        match doc {
+            Doc::Text(text) => {
+                output.push_str(text);
+                *column += text.chars().count();
+            }
+            Doc::Break(text) => {
+                if flat {
+                    output.push_str(text);
+                    *column += text.chars().count();
+                } else {
+                    newline(output, indent, column);
+                }
+            }
+            Doc::HardLine => newline(output, indent, column),
+            Doc::Nest(docs) => {
+                for doc in docs {
+                    self.render(output, doc, indent + self.indent, flat, column);
+                }
+            }
+            Doc::Group(docs) => {
+                let width = docs.iter().fold(*column, |width, doc| width.saturating_add(doc.flat_width()));
+                let flat = flat || width <= self.width;
+                for doc in docs {
+                    self.render(output, doc, indent, flat, column);
+                }
+            }
+        }
+    }
+}
+
+/// Starts a new line at the given indentation.
+fn newline(output: &mut String, indent: usize, column: &mut usize) {
+    let len = output.trim_end_matches(' ').len();
+    output.truncate(len);
+    output.push('\n');
+    let _ = write!(output, "{:indent$}", "");
+    *column = indent;
+}
+
+/// The tokens of a printed AST.
+#[derive(Debug)]
+enum Token<'a> {
+    /// A sequence of non whitespace characters that are not brackets.
+    Word(&'a str),
+
+    /// Whitespace that does not contain a newline.
+    Space,
+
+    /// A number of newlines, followed by the indentation of the next line.
+    Newline(usize, &'a str),
+
+    /// The tokens enclosed by an opening and closing bracket.
+    Group(char, Vec<Token<'a>>, Option<char>),
+}
+
+/// Splits the AST into tokens, where the brackets are nested into groups.
+fn tokenize(ast: &str) -> Vec<Token<'_>> {
+    let mut stack: Vec<(char, Vec<Token>)> = Vec::new();
+    let mut tokens = Vec::new();
+    let mut chars = ast.char_indices().peekable();
+
+    while let Some((start, ch)) = chars.next() {
+        match ch {
+            '(' | '[' | '{' => stack.push((ch, std::mem::take(&mut tokens))),
+            ')' | ']' | '}' => {
+                if let Some((open, parent)) = stack.pop() {
+                    let inner = std::mem::replace(&mut tokens, parent);
+                    tokens.push(Token::Group(open, inner, Some(ch)));
+                } else {
+                    tokens.push(Token::Word(&ast[start..start + 1]));
+                }
+            }
+            _ if ch.is_whitespace() => {
+                let mut end = start + ch.len_utf8();
+                while let Some(&(index, next)) = chars.peek() {
+                    if !next.is_whitespace() {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+
+                let whitespace = &ast[start..end];
+                let newlines = whitespace.matches('\n').count();
+                if newlines > 0 {
+                    let indentation = &whitespace[whitespace.rfind('\n').unwrap_or(0) + 1..];
+                    tokens.push(Token::Newline(newlines, indentation.trim_start_matches('\r')));
+                } else {
+                    tokens.push(Token::Space);
+                }
+            }
+            _ => {
+                let mut end = start + ch.len_utf8();
+                while let Some(&(index, next)) = chars.peek() {
+                    if next.is_whitespace() || "()[]{}".contains(next) {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+
+                tokens.push(Token::Word(&ast[start..end]));
+            }
+        }
+    }
+
+    // Close any unbalanced groups, which can only happen for malformed input.
+    while let Some((open, parent)) = stack.pop() {
+        let inner = std::mem::replace(&mut tokens, parent);
+        tokens.push(Token::Group(open, inner, None));
+    }
+
+    tokens
+}
+
+/// A document that describes the possible layouts.
+#[derive(Debug)]
+enum Doc {
+    /// Text that is printed as is.
+    Text(String),
+
+    /// A line break, which is printed as the given text when the enclosing group fits on a line.
+    Break(&'static str),
+
+    /// A line break that is always printed.
+    HardLine,
+
+    /// Documents that are indented by one level when broken.
+    Nest(Vec<Doc>),
+
+    /// Documents that are either printed on a single line, or whose breaks are all taken.
+    Group(Vec<Doc>),
+}
+
+impl Doc {
+    /// Returns the width of the document when it is printed on a single line.
+    fn flat_width(&self) -> usize {
+        match self {
+            Doc::Text(text) => text.chars().count(),
+            Doc::Break(text) => text.len(),
+            Doc::HardLine => usize::MAX,
+            Doc::Nest(docs) | Doc::Group(docs) => docs.iter().fold(0, |width, doc| width.saturating_add(doc.flat_width())),
+        }
+    }
+}
+
+/// Builds the documents for the top-level tokens, where every line is a separate group.
+fn build_statements(tokens: &[Token]) -> Vec<Doc> {
+    let mut result = Vec::new();
+    let mut start = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if let Token::Newline(count, indentation) = token {
+            // Preserve the newlines and indentation between the statements.
+            result.push(Doc::Group(build(&tokens[start..index])));
+            result.extend((0..*count).map(|_| Doc::HardLine));
+            result.push(Doc::Text(indentation.to_string()));
+            start = index + 1;
+        }
+    }
+
+    result.push(Doc::Group(build(&tokens[start..])));
+    result
+}
+
+/// Builds the documents for a sequence of tokens within a single group.
+fn build(tokens: &[Token]) -> Vec<Doc> {
+    let mut result = Vec::new();
+
+    let mut binder = false;
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Word(word) => {
+                binder |= BINDERS.contains(word);
+                result.push(Doc::Text(word.to_string()));
+            }
+            Token::Space | Token::Newline(..) => {
+                let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+                let after_operator = matches!(previous, Some(Token::Word(word)) if BREAK_AFTER.contains(word) || word.ends_with([',', '.']));
+                let after_binder = binder && matches!(previous, Some(Token::Word(word)) if word.ends_with('.'));
+
+                if after_binder {
+                    // The body of the binder extends to the end of the group.
+                    let mut body = vec![Doc::Break(" ")];
+                    body.extend(build(&tokens[index + 1..]));
+                    result.push(Doc::Nest(body));
+                    break;
+                }
+
+                result.push(if after_operator { Doc::Break(" ") } else { Doc::Text(" ".to_string()) });
+            }
+            Token::Group(open, inner, close) => {
+                // Spaces directly inside the brackets, such as for sets, are kept when printed on a single line.
+                let (leading, inner) = match inner.split_first() {
+                    Some((Token::Space, rest)) => (" ", rest),
+                    _ => ("", &inner[..]),
+                };
+                let (trailing, inner) = match inner.split_last() {
+                    Some((Token::Space, rest)) => (" ", rest),
+                    _ => ("", inner),
+                };
+
+                let mut nested = vec![Doc::Break(leading)];
+                nested.extend(build(inner));
+
+                let mut group = vec![Doc::Text(open.to_string()), Doc::Nest(nested), Doc::Break(trailing)];
+                if let Some(close) = close {
+                    group.push(Doc::Text(close.to_string()));
+                }
+
+                result.push(Doc::Group(group));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_fits() {
+        let layout = Layout::default();
+        let ast = "form nu X. ([a]((val((c) <= (N))) && (X)));\n";
+
+        assert_eq!(layout.format(ast), ast);
+    }
+
+    #[test]
+    fn test_layout_breaks() {
+        let layout = Layout { width: 30, indent: 2 };
+        let ast = "form forall x: Nat. ([a(x)]((val((x) < (10))) && (X({ x }))));";
+
+        assert_eq!(
+            layout.format(ast),
+            "form forall x: Nat.\n  (\n    [a(x)](\n      (val((x) < (10))) &&\n      (X({ x }))\n    )\n  );\n"
+        );
+    }
+
+    #[test]
+    fn test_layout_preserves_statements() {
+        let layout = Layout { width: 20, indent: 4 };
+        let ast = "act a;\n\nproc P = (a) . (P);\n  Q = a;\n";
+
+        assert_eq!(layout.format(ast), ast);
+    }
+}
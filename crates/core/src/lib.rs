@@ -0,0 +1,24 @@
+//! The comparison of the ASTs that the 202407.1 and 202507.0 releases of mCRL2 print for specifications and modal formulas.
+//!
+//! The [Checker] parses inputs with both releases, and its [CheckOutcome]
+//! describes whether they parse an input differently. The differences can be
+//! printed with [print_diff], and classified by the precedence [Rule] that
+//! causes them.
+
+#![forbid(unsafe_code)]
+
+mod backend;
+mod checker;
+mod diff;
+mod helper;
+mod layout;
+mod parentheses;
+mod rules;
+pub mod statements;
+
+pub use backend::*;
+pub use checker::*;
+pub use diff::*;
+pub use layout::*;
+pub use parentheses::*;
+pub use rules::*;
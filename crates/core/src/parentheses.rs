@@ -0,0 +1,208 @@
+//! Inserts the parentheses of a printed AST into the original text of a statement.
+//!
+//! The printed ASTs are fully parenthesized, so the grouping of a release can be
+//! kept by inserting its parentheses into the original text, which keeps the
+//! comments and layout of the statement. Only the parentheses around compound
+//! expressions are inserted, since those around identifiers are redundant.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::statements::SECTION_KEYWORDS;
+
+/// The significant characters of a statement, which excludes whitespace, comments and grouping parentheses.
+struct Significant {
+    /// The significant characters.
+    chars: Vec<char>,
+
+    /// The byte offset of every significant character in the text.
+    offsets: Vec<usize>,
+
+    /// The ranges of significant characters that are enclosed by grouping parentheses.
+    groups: Vec<Range<usize>>,
+}
+
+/// Returns the parentheses that must be inserted into the statement text to obtain the grouping of the printed statement.
+///
+/// The result consists of byte offsets in the text with the parentheses that must be inserted there, in increasing
+/// order. Returns None when the statement does not match the printed statement apart from its parentheses, or when its
+/// own parentheses conflict with the grouping.
+pub fn insert_parentheses(text: &str, grouping: &str) -> Option<Vec<(usize, String)>> {
+    let original = significant(text)?;
+    let printed = significant(grouping)?;
+    if original.chars != printed.chars {
+        return None;
+    }
+
+    // Parentheses of the statement itself must also occur in the printed statement.
+    if original
+        .groups
+        .iter()
+        .any(|group| !printed.groups.contains(group) && !is_atomic(&original.chars, group))
+    {
+        return None;
+    }
+
+    // The number of closing and opening parentheses at every offset, where closing parentheses come first.
+    let mut insertions: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for group in &printed.groups {
+        if original.groups.contains(group) || is_atomic(&printed.chars, group) {
+            continue;
+        }
+
+        let last = group.end - 1;
+        insertions.entry(original.offsets[group.start]).or_default().1 += 1;
+        insertions
+            .entry(original.offsets[last] + original.chars[last].len_utf8())
+            .or_default()
+            .0 += 1;
+    }
+
+    Some(
+        insertions
+            .into_iter()
+            .map(|(offset, (close, open))| (offset, ")".repeat(close) + &"(".repeat(open)))
+            .collect(),
+    )
+}
+
+/// Returns the significant characters of the text, after the section keywords, or None when its parentheses are unbalanced.
+///
+/// A parenthesis directly after an identifier, such as in `f(x)` or `val(b)`, is part of an application and is
+/// therefore significant, whereas other parentheses only group an expression.
+fn significant(text: &str) -> Option<Significant> {
+    let mut result = Significant {
+        chars: Vec::new(),
+        offsets: Vec::new(),
+        groups: Vec::new(),
+    };
+
+    // The open parentheses, with the start of their group or None for an application.
+    let mut open: Vec<Option<usize>> = Vec::new();
+    let mut previous = None;
+    let mut in_comment = false;
+
+    let start = skip_section_keywords(text);
+    for (index, ch) in text[start..].char_indices() {
+        let index = start + index;
+        if in_comment {
+            in_comment = ch != '\n';
+            continue;
+        }
+
+        match ch {
+            '%' => in_comment = true,
+            ch if ch.is_whitespace() => {}
+            '(' if !previous.is_some_and(is_identifier) => {
+                open.push(Some(result.chars.len()));
+                previous = Some(ch);
+            }
+            ')' if open.last()?.is_some() => {
+                let start = open.pop()??;
+                result.groups.push(start..result.chars.len());
+                previous = Some(ch);
+            }
+            _ => {
+                match ch {
+                    '(' => open.push(None),
+                    ')' => {
+                        open.pop();
+                    }
+                    _ => {}
+                }
+
+                result.chars.push(ch);
+                result.offsets.push(index);
+                previous = Some(ch);
+            }
+        }
+    }
+
+    open.is_empty().then_some(result)
+}
+
+/// Returns the offset after the leading comments and section keywords of the text, such as `proc` or `form`.
+fn skip_section_keywords(text: &str) -> usize {
+    let mut rest = text;
+    loop {
+        let trimmed = rest.trim_start();
+        if let Some(comment) = trimmed.strip_prefix('%') {
+            rest = &comment[comment.find('\n').unwrap_or(comment.len())..];
+        } else if let Some(keyword) = SECTION_KEYWORDS.iter().find(|keyword| {
+            trimmed
+                .strip_prefix(**keyword)
+                .is_some_and(|after| after.starts_with(char::is_whitespace) || after.starts_with('%'))
+        }) {
+            rest = &trimmed[keyword.len()..];
+        } else {
+            return text.len() - trimmed.len();
+        }
+    }
+}
+
+/// Returns true iff the character can be part of an identifier.
+fn is_identifier(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '\''
+}
+
+/// Returns true iff the group is an identifier, optionally applied to arguments, or a bracketed expression such as a set.
+///
+/// Parentheses around such a group never change the meaning of an expression.
+fn is_atomic(chars: &[char], group: &Range<usize>) -> bool {
+    let chars = &chars[group.clone()];
+    let name = chars.iter().take_while(|ch| is_identifier(**ch)).count();
+
+    match chars.get(name) {
+        None => name > 0,
+        Some('(' | '[' | '{') => {
+            // The bracket that starts after the name must be closed by the last character.
+            let mut depth = 0;
+            for (index, ch) in chars.iter().enumerate().skip(name) {
+                match ch {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth -= 1,
+                    _ => {}
+                }
+
+                if depth == 0 {
+                    return index == chars.len() - 1;
+                }
+            }
+
+            false
+        }
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies the insertions to the text.
+    fn apply(text: &str, insertions: &[(usize, String)]) -> String {
+        let mut result = text.to_string();
+        for (offset, parentheses) in insertions.iter().rev() {
+            result.insert_str(*offset, parentheses);
+        }
+        result
+    }
+
+    #[test]
+    fn test_insert_parentheses() {
+        let text = "P = a . % first\n  b + c";
+        let insertions = insert_parentheses(text, "proc P = (a) . ((b) + (c))").unwrap();
+        assert_eq!(apply(text, &insertions), "P = a . % first\n  (b + c)");
+
+        let insertions = insert_parentheses(text, "proc P = ((a) . (b)) + (c)").unwrap();
+        assert_eq!(apply(text, &insertions), "P = (a . % first\n  b) + c");
+
+        let text = "forall x:Nat. val(x < 2) && f(x)";
+        let insertions = insert_parentheses(text, "form forall x: Nat. ((val((x) < (2))) && (f(x)))").unwrap();
+        assert_eq!(apply(text, &insertions), "forall x:Nat. (val(x < 2) && f(x))");
+
+        // The statement does not match, or its own parentheses conflict with the grouping.
+        assert_eq!(insert_parentheses("P = a . b + d", "proc P = (a) . ((b) + (c))"), None);
+        assert_eq!(insert_parentheses("P = (a . b) + c", "proc P = (a) . ((b) + (c))"), None);
+    }
+}
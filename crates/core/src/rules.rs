@@ -0,0 +1,132 @@
+//! Classifies the parsing differences into the precedence rules that caused them.
+//!
+//! Both releases print fully parenthesized ASTs with the same operators in the
+//! same order, so the only change is the placement of the brackets. The rule is
+//! determined by the operators within the region in which the brackets differ.
+
+/// The rules by which the operator precedences changed between the releases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// The scope of a quantifier, fixpoint, lambda or sum operator changed.
+    BinderScope,
+
+    /// The grouping of an implication changed.
+    Implication,
+
+    /// The grouping of conjunctions and disjunctions changed.
+    ConjunctionDisjunction,
+
+    /// The grouping of the process operators changed.
+    ProcessOperators,
+
+    /// Any other change in grouping.
+    Other,
+}
+
+/// The binders whose scope can change.
+const BINDERS: [&str; 6] = ["forall", "exists", "nu", "mu", "lambda", "sum"];
+
+/// The process operators, excluding `||` which is also the disjunction.
+const PROCESS_OPERATORS: [&str; 8] = [".", "+", "<>", "->", "|", "||_", "<<", "@"];
+
+impl Rule {
+    /// Returns the identifier of the rule that is used in `% parse-checker: allow(...)` comments.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::BinderScope => "binder-scope",
+            Rule::Implication => "implication",
+            Rule::ConjunctionDisjunction => "conjunction-disjunction",
+            Rule::ProcessOperators => "process-operators",
+            Rule::Other => "other",
+        }
+    }
+
+    /// Returns the rule that explains the difference between the previous and current AST.
+    pub fn classify(previous: &str, current: &str) -> Rule {
+        let previous = tokenize(previous);
+        let current = tokenize(current);
+
+        // Determine the region in which the tokens, including the brackets, differ.
+        let prefix = previous
+            .iter()
+            .zip(current.iter())
+            .take_while(|(left, right)| left == right)
+            .count();
+        let suffix = previous[prefix..]
+            .iter()
+            .rev()
+            .zip(current[prefix..].iter().rev())
+            .take_while(|(left, right)| left == right)
+            .count();
+
+        let region: Vec<&str> = previous[prefix..previous.len() - suffix]
+            .iter()
+            .chain(current[prefix..current.len() - suffix].iter())
+            .copied()
+            .collect();
+
+        // A binder directly in front of the region also has a changed scope.
+        let binder_before = previous[..prefix]
+            .iter()
+            .rev()
+            .take_while(|token| !["(", ")"].contains(token))
+            .any(|token| BINDERS.contains(token));
+
+        if binder_before || region.iter().any(|token| BINDERS.contains(token)) {
+            Rule::BinderScope
+        } else if region.contains(&"=>") {
+            Rule::Implication
+        } else if region.iter().any(|token| ["&&", "||"].contains(token)) {
+            Rule::ConjunctionDisjunction
+        } else if region.iter().any(|token| PROCESS_OPERATORS.contains(token)) {
+            Rule::ProcessOperators
+        } else {
+            Rule::Other
+        }
+    }
+}
+
+/// Splits the AST into words and brackets.
+fn tokenize(ast: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+
+    for word in ast.split_whitespace() {
+        let mut start = 0;
+        for (index, ch) in word.char_indices() {
+            if "()[]{}".contains(ch) {
+                if start < index {
+                    result.push(&word[start..index]);
+                }
+                result.push(&word[index..index + 1]);
+                start = index + 1;
+            }
+        }
+
+        if start < word.len() {
+            result.push(&word[start..]);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            Rule::classify("form (a) && ((b) => (c))", "form ((a) && (b)) => (c)"),
+            Rule::Implication
+        );
+        assert_eq!(
+            Rule::classify("form forall x: Nat. ((a) && (b))", "form (forall x: Nat. (a)) && (b)"),
+            Rule::BinderScope
+        );
+        assert_eq!(
+            Rule::classify("init ((a) . (b)) + (c)", "init (a) . ((b) + (c))"),
+            Rule::ProcessOperators
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Splits mCRL2 specifications and modal formulas into their top-level statements.
+
+use std::ops::Range;
+
+/// A top-level statement, i.e., a declaration or equation that is terminated by a semicolon.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement<'a> {
+    /// The text of the statement, without the terminating semicolon and surrounding whitespace.
+    pub text: &'a str,
+
+    /// The byte range of the text within the input.
+    pub range: Range<usize>,
+
+    /// The comments on their own line before the statement, and those after its section keyword, without the leading `%`.
+    pub comments: Vec<&'a str>,
+
+    /// The keyword of the section that contains the statement, which is None for a formula without `form`.
+    pub section: Option<&'static str>,
+
+    /// The identifier that is declared or defined by the statement, which is None for `init` and `form`.
+    pub head: Option<&'a str>,
+}
+
+/// The keywords that start a section, after which comments still belong to the first statement of the section.
+pub(crate) const SECTION_KEYWORDS: [&str; 10] = [
+    "sort", "cons", "map", "var", "eqn", "act", "proc", "init", "form", "glob",
+];
+
+/// The sections whose statements do not start with a declared identifier.
+const ANONYMOUS_SECTIONS: [&str; 2] = ["init", "form"];
+
+/// The prefix of comments that contain directives for parse-checker.
+pub const DIRECTIVE_PREFIX: &str = "parse-checker:";
+
+impl Statement<'_> {
+    /// Returns the (one-based) line number on which the statement starts in the given input.
+    pub fn line(&self, input: &str) -> usize {
+        input[..self.range.start].matches('\n').count() + 1
+    }
+
+    /// Returns the section and head of the statement, which identify it regardless of the order of the sections.
+    ///
+    /// A formula without a section keyword belongs to `form`, since that is how it is printed.
+    pub fn key(&self) -> (&'static str, Option<&str>) {
+        (self.section.unwrap_or("form"), self.head)
+    }
+
+    /// Returns true iff one of the comments of the statement contains a directive.
+    pub fn has_directive(&self) -> bool {
+        self.comments.iter().any(|comment| directive(comment).is_some())
+    }
+
+    /// Returns true iff differences for the given rule are suppressed for this statement.
+    ///
+    /// A difference is suppressed by a `% parse-checker: ignore` comment, or by a
+    /// `% parse-checker: allow(rule-id, ...)` comment that lists the rule.
+    pub fn is_suppressed(&self, rule: &str) -> bool {
+        self.comments.iter().filter_map(|comment| directive(comment)).any(|directive| {
+            directive == "ignore"
+                || directive
+                    .strip_prefix("allow(")
+                    .and_then(|rules| rules.strip_suffix(')'))
+                    .is_some_and(|rules| rules.split(',').any(|allowed| allowed.trim() == rule))
+        })
+    }
+}
+
+/// Returns the directive in the given comment, which is the text after `parse-checker:`.
+pub fn directive(comment: &str) -> Option<&str> {
+    comment
+        .trim_start_matches('%')
+        .trim()
+        .strip_prefix(DIRECTIVE_PREFIX)
+        .map(str::trim)
+}
+
+/// Splits the input into its top-level statements.
+///
+/// Statements are separated by semicolons that do not occur within brackets
+/// or comments. The remaining text after the last semicolon is also a
+/// statement, since a modal formula does not need to be terminated.
+pub fn split_statements(input: &str) -> Vec<Statement<'_>> {
+    let mut result = Vec::new();
+    let mut depth: usize = 0;
+    let mut start = 0;
+    let mut in_comment = false;
+
+    for (index, ch) in input.char_indices() {
+        if in_comment {
+            in_comment = ch != '\n';
+            continue;
+        }
+
+        match ch {
+            '%' => in_comment = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                push_statement(&mut result, input, start..index);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    push_statement(&mut result, input, start..input.len());
+    result
+}
+
+/// Adds the statement in the given range of the input, ignoring leading and trailing whitespace and comments.
+fn push_statement<'a>(result: &mut Vec<Statement<'a>>, input: &'a str, range: Range<usize>) {
+    let mut start = range.start;
+    let mut end = range.end;
+    let mut comments = Vec::new();
+
+    // Skip leading whitespace and comments, so that the statement starts at its first token.
+    loop {
+        let text = &input[start..end];
+        let trimmed = text.trim_start();
+        start += text.len() - trimmed.len();
+
+        if let Some(comment) = trimmed.strip_prefix('%') {
+            let length = comment.find('\n').unwrap_or(comment.len());
+
+            // A comment on the same line as the end of the previous statement belongs to that statement.
+            if range.start == 0 || input[range.start..start].contains('\n') {
+                comments.push(&comment[..length]);
+            }
+
+            start += 1 + length;
+        } else {
+            break;
+        }
+    }
+
+    end = start + input[start..end].trim_end().len();
+    if start < end {
+        let text = &input[start..end];
+
+        // Comments directly after the section keywords, such as `proc`, belong to the first statement of the section.
+        let mut section = result.last().and_then(|statement| statement.section);
+        let mut rest = text;
+        loop {
+            let trimmed = rest.trim_start();
+            if let Some(comment) = trimmed.strip_prefix('%') {
+                let length = comment.find('\n').unwrap_or(comment.len());
+                comments.push(&comment[..length]);
+                rest = &comment[length..];
+            } else if let Some(keyword) = SECTION_KEYWORDS
+                .iter()
+                .find(|keyword| trimmed.strip_prefix(**keyword).is_some_and(|after| after.starts_with(char::is_whitespace)))
+            {
+                section = Some(*keyword);
+                rest = &trimmed[keyword.len()..];
+            } else {
+                break;
+            }
+        }
+
+        let head = section
+            .filter(|section| !ANONYMOUS_SECTIONS.contains(section))
+            .map(|_| {
+                let rest = rest.trim_start();
+                let length = rest
+                    .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '\''))
+                    .unwrap_or(rest.len());
+                &rest[..length]
+            })
+            .filter(|head| !head.is_empty());
+
+        result.push(Statement {
+            text,
+            range: start..end,
+            comments,
+            section,
+            head,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_statements() {
+        let input = "% A comment; with a semicolon\nsort D = struct d1 | d2;\n\nmap f: D -> Set(D);\n  g;\ninit f({ x: D | x == d1 });\n% trailing\n";
+        let statements = split_statements(input);
+
+        let texts: Vec<_> = statements.iter().map(|s| s.text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "sort D = struct d1 | d2",
+                "map f: D -> Set(D)",
+                "g",
+                "init f({ x: D | x == d1 })"
+            ]
+        );
+        assert_eq!(statements[0].line(input), 2);
+        assert_eq!(statements[1].line(input), 4);
+        assert_eq!(statements[3].line(input), 6);
+
+        let keys: Vec<_> = statements.iter().map(Statement::key).collect();
+        assert_eq!(
+            keys,
+            vec![("sort", Some("D")), ("map", Some("f")), ("map", Some("g")), ("init", None)]
+        );
+    }
+
+    #[test]
+    fn test_statement_comments() {
+        let input = "proc P = a; % parse-checker: ignore\n% parse-checker: allow(binder-scope, implication)\n  Q = b;\nproc\n  % parse-checker: ignore\n  R = c;\n";
+        let statements = split_statements(input);
+
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].comments.is_empty());
+        assert!(statements[1].is_suppressed("implication"));
+        assert!(!statements[1].is_suppressed("process-operators"));
+        assert!(statements[2].is_suppressed("process-operators"));
+    }
+
+    #[test]
+    fn test_split_unterminated_formula() {
+        let input = "nu X. [true]X && <a>true";
+        let statements = split_statements(input);
+
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].text, input);
+        assert_eq!(statements[0].line(input), 1);
+        assert_eq!(statements[0].key(), ("form", None));
+    }
+}
@@ -0,0 +1,22 @@
+use cargo_emit::rerun_if_changed;
+use mcrl2_build::Mcrl2Build;
+use mcrl2_build::PARSER_LIBRARIES;
+
+fn main() {
+    Mcrl2Build::new("../../3rd-party/mCRL2-2023/")
+        .source_root_env("MCRL2_2023_SRC_DIR")
+        .workaround_root("../../3rd-party/mCRL2-2023-workarounds/")
+        .workaround_root_env("MCRL2_2023_WORKAROUND_DIR")
+        .bridge("src/lib.rs")
+        .libraries(PARSER_LIBRARIES)
+        .define("MCRL2_NO_RECURSIVE_SOUNDNESS_CHECKS", "1") // These checks overflow the stack, and are extremely slow.
+        .define("LPS_NO_RECURSIVE_SOUNDNESS_CHECKS", "1")
+        // Enable thread safety since Rust executes its tests at least by default, and allow threading in general.
+        .define("MCRL2_ENABLE_MULTITHREADING", "0")
+        // Enables machine numbers
+        .define("MCRL2_ENABLE_MACHINENUMBERS", "0")
+        .compile("mcrl2-2023-sys");
+
+    // These files should trigger a rebuild.
+    rerun_if_changed!("cpp/print_ast.h");
+}
@@ -0,0 +1,56 @@
+//! A helper executable that prints the AST of mCRL2 specifications and modal formulas using the 202301.0 release.
+//!
+//! The parse-checker runs it as a separate process, since the libraries of different
+//! releases of mCRL2 cannot be linked into one process.
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+use std::error::Error;
+
+use clap::Parser;
+
+#[derive(Parser)]
+#[command()]
+#[command(name = "mcrl2-2023",
+    author = "Maurice Laveaux",
+    version,
+    about = "Internal tool used to print the 2023 AST, use parse-checker instead!")]
+struct Cli {
+    /// Whether to check mCRL2 specifications (default) or modal formulas.
+    #[arg(short, long, default_value_t = false)]
+    mcf: bool,
+
+    /// Keeps reading requests from stdin until it is closed, so that one process can be used for many inputs.
+    ///
+    /// Every request is a line `mcf <length>` or `mcrl2 <length>` followed by the input of that many bytes.
+    /// Every response is a line `ok <length>` or `error <length>` followed by the AST or error message.
+    #[arg(long, default_value_t = false)]
+    server: bool,
+}
+
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    if cli.server {
+        mcrl2_helper::serve(&mut io::stdin().lock(), &mut io::stdout().lock(), |input, mcf| {
+            if mcf {
+                mcrl2_2023_sys::ffi::print_ast_mcf(input)
+            } else {
+                mcrl2_2023_sys::ffi::print_ast_mcrl2(input)
+            }
+        })?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    if cli.mcf {
+        print!("{}", mcrl2_2023_sys::ffi::print_ast_mcf(&input)?);
+    } else {
+        // Default to checking mCRL2 specifications
+        print!("{}", mcrl2_2023_sys::ffi::print_ast_mcrl2(&input)?);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,345 @@
+//!
+//! Builds the libraries of an mCRL2 source tree for the build scripts of the sys crates.
+//!
+//! Every sys crate links one release of mCRL2, and only differs in the location of
+//! its sources and the libraries that it needs. Relative paths, including those in
+//! the environment variables, are resolved against the directory of the crate that
+//! is built, since cargo runs its build script there. For example:
+//!
+//! ```no_run
+//! use mcrl2_build::Mcrl2Build;
+//! use mcrl2_build::PARSER_LIBRARIES;
+//!
+//! Mcrl2Build::new("../../3rd-party/mCRL2/")
+//!     .source_root_env("MCRL2_SRC_DIR")
+//!     .workaround_root("../../3rd-party/mCRL2-workarounds/")
+//!     .workaround_root_env("MCRL2_WORKAROUND_DIR")
+//!     .bridge("src/lib.rs")
+//!     .libraries(PARSER_LIBRARIES)
+//!     .define("MCRL2_ENABLE_MULTITHREADING", "0")
+//!     .compile("mcrl2-sys");
+//! ```
+
+#![forbid(unsafe_code)]
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use cargo_emit::rerun_if_changed;
+use cargo_emit::rerun_if_env_changed;
+use cc::Build;
+
+/// The source files of the mCRL2 libraries that are needed to parse and print specifications and modal formulas.
+pub const PARSER_LIBRARIES: &[(&str, &[&str])] = &[
+    (
+        "atermpp",
+        &[
+            "aterm_implementation.cpp",
+            "aterm_io_binary.cpp",
+            "aterm_io_text.cpp",
+            "function_symbol.cpp",
+            "function_symbol_pool.cpp",
+        ],
+    ),
+    ("lps", &["lps.cpp", "lps_io.cpp"]),
+    ("modal_formula", &["modal_formula.cpp", "regfrmtrans.cpp"]),
+    (
+        "data",
+        &[
+            "data.cpp",
+            "data_io.cpp",
+            "data_specification.cpp",
+            "machine_word.cpp",
+            "typecheck.cpp",
+            "detail/rewrite/jitty.cpp",
+            "detail/rewrite/rewrite.cpp",
+            "detail/rewrite/strategy.cpp",
+        ],
+    ),
+    (
+        "utilities",
+        &["bitstream.cpp", "cache_metric.cpp", "logger.cpp", "text_utility.cpp"],
+    ),
+    ("core", &["dparser.cpp", "core.cpp"]),
+    ("process", &["process.cpp"]),
+];
+
+/// The source files of the dparser library that is included in the mCRL2 sources.
+const DPARSER_SOURCE_FILES: &[&str] = &[
+    "arg.c",
+    "parse.c",
+    "scan.c",
+    "dsymtab.c",
+    "util.c",
+    "read_binary.c",
+    "dparse_tree.c",
+];
+
+/// The source files of make_dparser, which generates the grammar tables of mCRL2 together with the dparser library.
+const MAKE_DPARSER_SOURCE_FILES: &[&str] = &[
+    "make_dparser.c",
+    "mkdparse.c",
+    "write_tables.c",
+    "grammar.g.c",
+    "gram.c",
+    "lex.c",
+    "lr.c",
+    "version.c",
+];
+
+/// A builder for the static libraries of an mCRL2 source tree, together with the cxx bridges that use them.
+pub struct Mcrl2Build {
+    /// The root of the mCRL2 sources.
+    source_root: PathBuf,
+
+    /// The files that are normally generated by the CMake build of mCRL2, such as the grammar tables of dparser.
+    workaround_root: Option<PathBuf>,
+
+    /// The root of the boost headers.
+    boost_root: PathBuf,
+
+    /// The Rust files that define a cxx bridge.
+    bridges: Vec<PathBuf>,
+
+    /// The libraries of mCRL2 with the source files that are compiled, relative to their source directory.
+    libraries: Vec<(String, Vec<String>)>,
+
+    /// The preprocessor definitions of the mCRL2 sources.
+    defines: Vec<(String, String)>,
+}
+
+impl Mcrl2Build {
+    /// Returns a builder for the mCRL2 sources at the given root, which is relative to the crate that is built.
+    pub fn new(source_root: impl AsRef<Path>) -> Self {
+        Self {
+            source_root: source_root.as_ref().to_path_buf(),
+            workaround_root: None,
+            boost_root: PathBuf::from("../../3rd-party/boost-include-only/"),
+            bridges: Vec::new(),
+            libraries: Vec::new(),
+            defines: Vec::new(),
+        }
+    }
+
+    /// Uses the source root in the given environment variable instead, when it is set.
+    pub fn source_root_env(mut self, variable: &str) -> Self {
+        if let Some(source_root) = env_path(variable) {
+            self.source_root = source_root;
+        }
+
+        self
+    }
+
+    /// Sets the location of the generated files, which contains an `include` directory and optionally `mcrl2_syntax.c`.
+    ///
+    /// Must be set, since the mCRL2 sources cannot be compiled without these files. When `mcrl2_syntax.c` is
+    /// missing, the grammar tables are generated from the mCRL2 sources instead, see [Mcrl2Build::compile].
+    pub fn workaround_root(mut self, workaround_root: impl AsRef<Path>) -> Self {
+        self.workaround_root = Some(workaround_root.as_ref().to_path_buf());
+        self
+    }
+
+    /// Uses the workaround root in the given environment variable instead, when it is set.
+    ///
+    /// Must be called after [Mcrl2Build::workaround_root], which would otherwise replace it.
+    pub fn workaround_root_env(mut self, variable: &str) -> Self {
+        if let Some(workaround_root) = env_path(variable) {
+            self.workaround_root = Some(workaround_root);
+        }
+
+        self
+    }
+
+    /// Sets the location of the boost headers, which defaults to the boost-include-only submodule.
+    pub fn boost_root(mut self, boost_root: impl AsRef<Path>) -> Self {
+        self.boost_root = boost_root.as_ref().to_path_buf();
+        self
+    }
+
+    /// Adds a Rust file that defines a cxx bridge.
+    pub fn bridge(mut self, path: impl AsRef<Path>) -> Self {
+        self.bridges.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Compiles the given source files of the library `libraries/<name>`, and adds its include directory.
+    pub fn library(mut self, name: &str, source_files: &[&str]) -> Self {
+        self.libraries.push((
+            name.to_string(),
+            source_files.iter().map(|file| file.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Compiles the given libraries, see [Mcrl2Build::library].
+    pub fn libraries(self, libraries: &[(&str, &[&str])]) -> Self {
+        libraries
+            .iter()
+            .fold(self, |build, (name, source_files)| build.library(name, source_files))
+    }
+
+    /// Adds a preprocessor definition for the mCRL2 sources.
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Compiles dparser, the libraries and the bridges into static libraries, where the bridges are named `name`.
+    ///
+    /// The grammar tables are generated by the CMake build of mCRL2, and are not part of its sources. They are taken
+    /// from `mcrl2_syntax.c` in the workaround root, or otherwise generated with the make_dparser tool of the sources.
+    pub fn compile(self, name: &str) {
+        let dparser_path = self.source_root.join("3rd-party/dparser");
+        let workaround_root = self.workaround_root.as_ref().expect("the workaround root must be set");
+
+        let mut mcrl2_syntax = workaround_root.join("mcrl2_syntax.c");
+        if !mcrl2_syntax.exists() {
+            mcrl2_syntax = self.generate_grammar(&dparser_path);
+        }
+
+        // Build dparser separately since it's a C library.
+        let mut build_dparser = Build::new();
+        build_dparser
+            .include(&dparser_path)
+            .files(DPARSER_SOURCE_FILES.iter().map(|file| dparser_path.join(file)));
+
+        add_compile_flags(&mut build_dparser, &self.source_root);
+        build_dparser.compile("dparser");
+
+        // These are the files for which we need to call cxxbuild to produce the bridge code.
+        let mut build = cxx_build::bridges(&self.bridges);
+
+        // Additional files needed to compile the bridge, basically to build mCRL2 itself.
+        build.cpp(true).warnings(false).include(&dparser_path);
+
+        for (library, source_files) in &self.libraries {
+            let library_path = self.source_root.join("libraries").join(library);
+            build
+                .include(library_path.join("include"))
+                .files(source_files.iter().map(|file| library_path.join("source").join(file)));
+        }
+
+        build
+            .include(workaround_root.join("include"))
+            .include(&self.boost_root)
+            .file(mcrl2_syntax);
+
+        for (name, value) in &self.defines {
+            build.define(name, value.as_str());
+        }
+
+        // Disable assertions and other checks in release mode.
+        let profile = env::var("PROFILE").expect("cargo should always set this variable");
+        match profile.as_str() {
+            "debug" => {
+                build.define("_LIBCPP_DEBUG", "1");
+                build.define("_LIBCPP_ENABLE_THREAD_SAFETY_ANNOTATIONS", "1");
+                build.define("_LIBCPP_HARDENING_MODE", "_LIBCPP_HARDENING_MODE_DEBUG");
+                build.define("_LIBCPP_ABI_BOUNDED_ITERATORS ", "1");
+                build.define("_LIBCPP_ABI_BOUNDED_ITERATORS_IN_STRING", "1");
+                build.define("_LIBCPP_ABI_BOUNDED_ITERATORS_IN_VECTOR", "1");
+                build.define("_LIBCPP_ABI_BOUNDED_UNIQUE_PTR", "1");
+                build.define("_LIBCPP_ABI_BOUNDED_ITERATORS_IN_STD_ARRAY", "1");
+            }
+            "release" => {
+                build.define("NDEBUG", "1");
+            }
+            _ => {
+                panic!("Unsupported profile {}", profile);
+            }
+        }
+
+        add_compile_flags(&mut build, &self.source_root);
+        build.compile(name);
+    }
+
+    /// Generates the grammar tables of `libraries/core/source/mcrl2_syntax.g` in OUT_DIR, in the same way as the CMake
+    /// build of mCRL2, and returns the path of the generated file.
+    fn generate_grammar(&self, dparser_path: &Path) -> PathBuf {
+        let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo should always set this variable"));
+        let grammar = self.source_root.join("libraries/core/source/mcrl2_syntax.g");
+        rerun_if_changed!(grammar.display());
+
+        // make_dparser runs on the host, so it is compiled with the compiler of the host.
+        let host = env::var("HOST").expect("cargo should always set this variable");
+        let compiler = Build::new().cargo_metadata(false).target(&host).get_compiler();
+        let make_dparser = out_dir.join(if cfg!(windows) { "make_dparser.exe" } else { "make_dparser" });
+
+        let mut command = compiler.to_command();
+        command
+            .arg(format!("-I{}", dparser_path.display()))
+            .args(
+                MAKE_DPARSER_SOURCE_FILES
+                    .iter()
+                    .chain(DPARSER_SOURCE_FILES)
+                    .map(|file| dparser_path.join(file)),
+            );
+        if compiler.is_like_msvc() {
+            command.arg(format!("/Fe{}", make_dparser.display()));
+        } else {
+            command.arg("-o").arg(&make_dparser).arg("-lm");
+        }
+        run(&mut command, "compile make_dparser");
+
+        // The tables are written next to the grammar, so it is copied to OUT_DIR first.
+        fs::copy(&grammar, out_dir.join("mcrl2_syntax.g"))
+            .unwrap_or_else(|error| panic!("Cannot copy {}: {}", grammar.display(), error));
+        run(
+            Command::new(&make_dparser)
+                .current_dir(&out_dir)
+                .args(["-A", "-H0", "-i", "mcrl2", "mcrl2_syntax.g"]),
+            "generate the grammar tables",
+        );
+
+        out_dir.join("mcrl2_syntax.g.d_parser.c")
+    }
+}
+
+/// Returns the path in the given environment variable, and rebuilds the crate when it changes.
+fn env_path(variable: &str) -> Option<PathBuf> {
+    rerun_if_env_changed!(variable);
+    env::var_os(variable).map(PathBuf::from)
+}
+
+/// Runs the command of the build script, and panics with the given description when it fails.
+fn run(command: &mut Command, description: &str) {
+    let status = command
+        .status()
+        .unwrap_or_else(|error| panic!("Cannot {}, running {:?} failed: {}", description, command, error));
+    if !status.success() {
+        panic!("Cannot {}, {:?} exited with {}", description, command, status);
+    }
+}
+
+/// Add platform specific compile flags and definitions.
+#[allow(unused_variables)]
+fn add_compile_flags(build: &mut Build, mcrl2_path: &Path) {
+    #[cfg(unix)]
+    build
+        .flag_if_supported("-Wall")
+        .flag_if_supported("-pipe")
+        .flag_if_supported("-pedantic")
+        .flag_if_supported("-stdlib=libc++")
+        .flag_if_supported("-std=c++17");
+
+    #[cfg(windows)]
+    build
+        .include(mcrl2_path.join("build/workarounds/msvc")) // These are MSVC workarounds that mCRL2 relies on for compilation.
+        .flag("/EHs")
+        .flag("/bigobj")
+        .flag("/W3")
+        .flag("/MP")
+        .flag("/Zc:inline")
+        .flag("/permissive-")
+        .flag("/std:c++17")
+        .define("WIN32", "1")
+        .define("WIN32_LEAN_AND_MEAN", "1")
+        .define("NOMINMAX", "1")
+        .define("_USE_MATH_DEFINES", "1")
+        .define("_CRT_SECURE_CPP_OVERLOAD_STANDARD_NAMES", "1")
+        .define("_CRT_SECURE_NO_WARNINGS", "1")
+        .define("BOOST_ALL_NO_LIB", "1");
+}
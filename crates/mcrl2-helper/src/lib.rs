@@ -0,0 +1,107 @@
+//! The protocol between parse-checker and the helper executables, such as `mcrl2-2024`.
+//!
+//! Every helper links an older release of mCRL2, and with `--server` it answers
+//! requests on stdin until it is closed. Every request is a line `mcf <length>`
+//! or `mcrl2 <length>` followed by the input of that many bytes, and every
+//! response is a line `ok <length>` or `error <length>` followed by the AST or
+//! the error message of the parser.
+
+#![forbid(unsafe_code)]
+
+use std::error::Error;
+use std::fmt::Display;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+/// Writes a message, which is a line with the tag and the length of the payload in bytes followed by the payload.
+pub fn write_message(writer: &mut impl Write, tag: &str, payload: &str) -> io::Result<()> {
+    write!(writer, "{} {}\n{}", tag, payload.len(), payload)?;
+    writer.flush()
+}
+
+/// Reads a message written by [write_message], and returns its tag and payload or None when the input is closed.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<(String, String)>> {
+    let mut header = String::new();
+    if reader.read_line(&mut header)? == 0 {
+        return Ok(None);
+    }
+
+    let invalid = |message: &str| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", message, header.trim_end()))
+    };
+    let (tag, length) = header.trim_end().split_once(' ').ok_or_else(|| invalid("Invalid header"))?;
+    let length: usize = length.parse().map_err(|_| invalid("Invalid length in header"))?;
+
+    let mut payload = vec![0; length];
+    reader.read_exact(&mut payload)?;
+    let payload = String::from_utf8(payload).map_err(|_| invalid("Invalid UTF-8 in the message with header"))?;
+
+    Ok(Some((tag.to_string(), payload)))
+}
+
+/// Answers the requests of the reader until it is closed, where `print_ast` prints an mCRL2 specification, or a modal
+/// formula when its flag is set.
+pub fn serve<E: Display>(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    print_ast: impl Fn(&str, bool) -> Result<String, E>,
+) -> Result<(), Box<dyn Error>> {
+    while let Some((kind, input)) = read_message(reader)? {
+        let mcf = match kind.as_str() {
+            "mcf" => true,
+            "mcrl2" => false,
+            _ => return Err(format!("Invalid request kind {}", kind).into()),
+        };
+
+        match print_ast(&input, mcf) {
+            Ok(ast) => write_message(writer, "ok", &ast)?,
+            Err(error) => write_message(writer, "error", &error.to_string())?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serve_round_trip() {
+        let mut requests = Vec::new();
+        write_message(&mut requests, "mcf", "[true*]<\u{e9}>true").unwrap();
+        write_message(&mut requests, "mcrl2", "error \u{1d49c}").unwrap();
+        write_message(&mut requests, "mcrl2", "").unwrap();
+
+        // The lengths are in bytes, so the multi-byte characters must not end the messages early.
+        let mut responses = Vec::new();
+        serve(&mut requests.as_slice(), &mut responses, |input, mcf| {
+            if input.starts_with("error") {
+                Err(format!("cannot parse {}", input))
+            } else {
+                Ok(format!("{} {}", if mcf { "form" } else { "init" }, input))
+            }
+        })
+        .unwrap();
+
+        let mut reader = responses.as_slice();
+        let mut read = || read_message(&mut reader).unwrap();
+        assert_eq!(read(), Some(("ok".to_string(), "form [true*]<\u{e9}>true".to_string())));
+        assert_eq!(read(), Some(("error".to_string(), "cannot parse error \u{1d49c}".to_string())));
+        assert_eq!(read(), Some(("ok".to_string(), "init ".to_string())));
+        assert_eq!(read(), None);
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        assert!(read_message(&mut "ok\nabc".as_bytes()).is_err());
+        assert!(read_message(&mut "ok 10\nabc".as_bytes()).is_err());
+        assert!(read_message(&mut "ok 2\n\u{e9}".as_bytes()).is_ok());
+        assert!(read_message(&mut "ok 1\n\u{e9}".as_bytes()).is_err());
+
+        let mut requests = Vec::new();
+        write_message(&mut requests, "lps", "").unwrap();
+        assert!(serve(&mut requests.as_slice(), &mut Vec::new(), |_, _| Ok::<_, String>(String::new())).is_err());
+    }
+}
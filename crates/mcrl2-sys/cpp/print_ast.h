@@ -25,4 +25,12 @@ rust::String print_ast_mcf(rust::Str text) {
     std::stringstream result;
     result << mcrl2::state_formulas::pp(formspec, false) << std::endl;
     return result.str();
-}
\ No newline at end of file
+}
+
+inline
+void type_check_mcf(rust::Str specification, rust::Str formula) {
+    mcrl2::process::process_specification spec = mcrl2::process::parse_process_specification(static_cast<std::string>(specification));
+
+    // Parsing a state formula for a specification also type checks it against the sorts and actions of that specification.
+    mcrl2::state_formulas::parse_state_formula(static_cast<std::string>(formula), spec, false);
+}
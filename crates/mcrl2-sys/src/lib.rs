@@ -20,6 +20,9 @@ mod ffi {
 
         /// Prints the input MCF specification as an abstract syntax tree (AST).
         fn print_ast_mcf(input: &str) -> Result<String>;
+
+        /// Type checks the input modal formula against the given mCRL2 specification.
+        fn type_check_mcf(specification: &str, formula: &str) -> Result<()>;
     }
 }
 
@@ -35,4 +38,10 @@ pub fn print_ast_mcrl2(input: &str) -> Result<String, cxx::Exception> {
 pub fn print_ast_mcf(input: &str) -> Result<String, cxx::Exception> {
     let _guard = MCRL2_LOCK.lock().unwrap();
     ffi::print_ast_mcf(input)
+}
+
+/// Type checks the input modal formula against the given mCRL2 specification.
+pub fn type_check_mcf(specification: &str, formula: &str) -> Result<(), cxx::Exception> {
+    let _guard = MCRL2_LOCK.lock().unwrap();
+    ffi::type_check_mcf(specification, formula)
 }
\ No newline at end of file
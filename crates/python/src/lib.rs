@@ -0,0 +1,258 @@
+//! A Python extension module that compares how the 202407.1 and 202507.0 releases of mCRL2 parse their inputs.
+//!
+//! The module wraps the [Checker] of `parse-checker-core`, where one
+//! `mcrl2-2024` helper process is reused for all calls. The GIL is released
+//! while the inputs are parsed, so other Python threads can run. For example:
+//!
+//! ```python
+//! import parse_checker
+//!
+//! result = parse_checker.compare("[true*]<true>true", "mcf")
+//! for difference in result.differences:
+//!     print(difference.rule, difference.previous, difference.current)
+//! ```
+
+#![forbid(unsafe_code)]
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use parse_checker_core::Checker;
+use parse_checker_core::InputKind;
+use parse_checker_core::Outcome;
+use parse_checker_core::ParseError;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// The checker that is used by all functions, which is replaced by [set_helper_path].
+static CHECKER: LazyLock<Mutex<Checker>> = LazyLock::new(|| Mutex::new(Checker::with_persistent_helper(None)));
+
+/// The result of parsing an input with one release.
+#[pyclass(frozen, get_all, module = "parse_checker")]
+#[derive(Clone)]
+struct ParseResult {
+    /// The release that parsed the input.
+    release: String,
+
+    /// The fully parenthesized AST, or None when the input cannot be parsed.
+    ast: Option<String>,
+
+    /// The error message of the parser, or None when the input can be parsed.
+    error: Option<String>,
+}
+
+#[pymethods]
+impl ParseResult {
+    /// Returns true iff the input can be parsed.
+    #[getter]
+    fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn __repr__(&self) -> String {
+        match (&self.ast, &self.error) {
+            (_, Some(error)) => format!("ParseResult(release={:?}, error={:?})", self.release, error),
+            (ast, None) => format!("ParseResult(release={:?}, ast={:?})", self.release, ast.as_deref().unwrap_or("")),
+        }
+    }
+}
+
+/// A top-level statement that is parsed differently by both releases.
+#[pyclass(frozen, get_all, module = "parse_checker")]
+#[derive(Clone)]
+struct Difference {
+    /// The precedence rule that caused the difference, for example `conjunction-disjunction`.
+    rule: String,
+
+    /// The line of the statement in the input, or None when it cannot be determined.
+    line: Option<usize>,
+
+    /// The statement as parsed by the 202407.1 release.
+    previous: String,
+
+    /// The statement as parsed by the 202507.0 release.
+    current: String,
+}
+
+#[pymethods]
+impl Difference {
+    fn __repr__(&self) -> String {
+        format!(
+            "Difference(rule={:?}, line={}, previous={:?}, current={:?})",
+            self.rule,
+            self.line.map_or_else(|| "None".to_string(), |line| line.to_string()),
+            self.previous,
+            self.current
+        )
+    }
+}
+
+/// The result of parsing an input with both releases.
+#[pyclass(frozen, get_all, name = "Comparison", module = "parse_checker")]
+struct PyComparison {
+    /// The outcome: `identical`, `difference` or `parse-error`.
+    status: String,
+
+    /// The result of the 202407.1 release.
+    previous: ParseResult,
+
+    /// The result of the 202507.0 release.
+    current: ParseResult,
+
+    /// The statements that are parsed differently, excluding those suppressed by a comment.
+    differences: Vec<Difference>,
+}
+
+#[pymethods]
+impl PyComparison {
+    fn __repr__(&self) -> String {
+        format!(
+            "Comparison(status={:?}, differences={})",
+            self.status,
+            self.differences.len()
+        )
+    }
+}
+
+/// Returns the input kind for its name.
+fn input_kind(kind: &str) -> PyResult<InputKind> {
+    match kind {
+        "mcf" => Ok(InputKind::Mcf),
+        "mcrl2" => Ok(InputKind::Mcrl2),
+        "data" => Ok(InputKind::DataExpression),
+        _ => Err(PyValueError::new_err(format!(
+            "Unknown input kind {:?}, expected \"mcf\", \"mcrl2\" or \"data\"",
+            kind
+        ))),
+    }
+}
+
+/// Returns the parse result of a release, where errors other than parse errors are raised.
+fn parse_result(release: &str, result: Result<String, Box<dyn Error>>) -> PyResult<ParseResult> {
+    match result {
+        Ok(ast) => Ok(ParseResult {
+            release: release.to_string(),
+            ast: Some(ast),
+            error: None,
+        }),
+        Err(error) => match error.downcast::<ParseError>() {
+            Ok(error) => Ok(ParseResult {
+                release: release.to_string(),
+                ast: None,
+                error: Some(error.message),
+            }),
+            Err(error) => Err(PyRuntimeError::new_err(error.to_string())),
+        },
+    }
+}
+
+/// Parses the text with mCRL2 202507.0, where the kind is "mcf", "mcrl2" or "data".
+#[pyfunction]
+#[pyo3(signature = (text, kind = "mcf"))]
+fn parse_2025(py: Python<'_>, text: &str, kind: &str) -> PyResult<ParseResult> {
+    let kind = input_kind(kind)?;
+    py.detach(|| parse_result("202507.0", CHECKER.lock().unwrap().print_ast_current(text, kind)))
+}
+
+/// Parses the text with mCRL2 202407.1, using the mcrl2-2024 helper, where the kind is "mcf", "mcrl2" or "data".
+#[pyfunction]
+#[pyo3(signature = (text, kind = "mcf"))]
+fn parse_2024(py: Python<'_>, text: &str, kind: &str) -> PyResult<ParseResult> {
+    let kind = input_kind(kind)?;
+    py.detach(|| parse_result("202407.1", CHECKER.lock().unwrap().print_ast_previous(text, kind)))
+}
+
+/// Parses the text with both releases, and returns the statements that are parsed differently.
+#[pyfunction]
+#[pyo3(signature = (text, kind = "mcf"))]
+fn compare(py: Python<'_>, text: &str, kind: &str) -> PyResult<PyComparison> {
+    let kind = input_kind(kind)?;
+    py.detach(|| compare_input(text, kind))
+}
+
+/// Parses the input with both releases, which is called without holding the GIL.
+fn compare_input(text: &str, kind: InputKind) -> PyResult<PyComparison> {
+    let outcome = CHECKER.lock().unwrap().check(text, kind);
+
+    let comparison = parse_checker_core::Comparison {
+        current: outcome.current.as_ref().map_or_else(|_| String::new(), Clone::clone),
+        previous: outcome.previous.as_ref().map_or_else(|_| String::new(), Clone::clone),
+    };
+
+    let differences = match outcome.outcome {
+        Outcome::Difference => comparison
+            .differences(text)
+            .iter()
+            .filter(|difference| !difference.is_suppressed())
+            .map(|difference| Difference {
+                rule: difference.rule().id().to_string(),
+                line: difference.original.as_ref().map(|original| original.line(text)),
+                previous: difference.previous.to_string(),
+                current: difference.current.to_string(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let status = match outcome.outcome {
+        Outcome::Identical => "identical",
+        Outcome::Difference => "difference",
+        Outcome::ParseError => "parse-error",
+        Outcome::InternalError => "internal-error",
+    };
+
+    Ok(PyComparison {
+        status: status.to_string(),
+        previous: parse_result("202407.1", outcome.previous)?,
+        current: parse_result("202507.0", outcome.current)?,
+        differences,
+    })
+}
+
+/// Uses the mcrl2-2024 helper at the given path, which is otherwise searched for in PATH.
+#[pyfunction]
+fn set_helper_path(path: PathBuf) {
+    *CHECKER.lock().unwrap() = Checker::with_persistent_helper(Some(path));
+}
+
+#[pymodule]
+fn parse_checker(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<ParseResult>()?;
+    m.add_class::<Difference>()?;
+    m.add_class::<PyComparison>()?;
+    m.add_function(wrap_pyfunction!(parse_2025, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_2024, m)?)?;
+    m.add_function(wrap_pyfunction!(compare, m)?)?;
+    m.add_function(wrap_pyfunction!(set_helper_path, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_result() {
+        assert!(matches!(input_kind("mcf"), Ok(InputKind::Mcf)));
+        assert!(matches!(input_kind("data"), Ok(InputKind::DataExpression)));
+        assert!(input_kind("lps").is_err());
+
+        let result = parse_result("202507.0", Ok("form true".to_string())).unwrap();
+        assert!(result.ok());
+        assert_eq!(result.ast.as_deref(), Some("form true"));
+
+        let error = ParseError {
+            release: "202507.0".to_string(),
+            message: "syntax error".to_string(),
+        };
+        let result = parse_result("202507.0", Err(error.into())).unwrap();
+        assert!(!result.ok());
+        assert_eq!(result.error.as_deref(), Some("syntax error"));
+
+        // Errors other than parse errors are raised.
+        assert!(parse_result("202407.1", Err("The helper cannot be found".into())).is_err());
+    }
+}
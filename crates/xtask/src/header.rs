@@ -0,0 +1,36 @@
+//!
+//! Header command for generating the C header of the parse-checker-capi crate.
+//!
+
+use std::env;
+use std::error::Error;
+use std::fs;
+
+/// Generates the checked-in C header `crates/capi/include/parse_checker.h` with cbindgen.
+///
+/// With `check` set the header is not written, and an error is returned when it is not up to date. This is used by
+/// the CI to check that the header matches the C API.
+pub fn header(check: bool) -> Result<(), Box<dyn Error>> {
+    let crate_dir = env::current_dir()?.join("crates").join("capi");
+    let header_path = crate_dir.join("include").join("parse_checker.h");
+
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))?)
+        .generate()?
+        .write(&mut generated);
+
+    if check {
+        if fs::read(&header_path).ok().as_ref() != Some(&generated) {
+            return Err(format!("{} is not up to date, run `cargo xtask header`", header_path.display()).into());
+        }
+
+        println!("{} is up to date", header_path.display());
+    } else {
+        fs::write(&header_path, generated)?;
+        println!("Generated {}", header_path.display());
+    }
+
+    Ok(())
+}
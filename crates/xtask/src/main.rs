@@ -8,6 +8,7 @@ use std::env;
 use std::error::Error;
 use std::process::ExitCode;
 
+mod header;
 mod package;
 
 fn main() -> Result<ExitCode, Box<dyn Error>> {
@@ -20,6 +21,7 @@ fn main() -> Result<ExitCode, Box<dyn Error>> {
     let task = args.next();
 
     match task.as_deref() {
+        Some("header") => header::header(args.next().as_deref() == Some("--check"))?,
         Some("package") => package::package()?,
         Some(x) => {
             println!("Unknown task {x}");
@@ -36,6 +38,6 @@ fn main() -> Result<ExitCode, Box<dyn Error>> {
 /// Print the help message.
 fn print_help() {
     println!(
-        "Available tasks: package"
+        "Available tasks: header [--check], package"
     );
 }